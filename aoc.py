@@ -8,7 +8,6 @@ import argparse
 import requests
 import colorama
 from colorama import Fore
-from shutil import copyfile
 from bs4 import BeautifulSoup
 from dotenv import load_dotenv
 
@@ -16,16 +15,8 @@ INPUT_URL = "https://adventofcode.com/{year}/day/{day}/input"
 EXAMPLE_URL = "https://adventofcode.com/{year}/day/{day}"
 DEFAULT_OUTPUT = "inputs/day{day}{suffix}.txt"
 
+# New day modules are registered by build.rs, no other files need to be modified
 TEMPLATES = {"src/day_template.rs": "src/day_{day}.rs"}
-INSERT_FILES = {
-    "Cargo.toml": {
-        "# Insert new day feature here": ["day_{day} = []"],
-    },
-    "src/main.rs": {
-        "// Include new day mod here": ['#[cfg(feature = "day_{day}")]', "mod day_{day};"],
-        "// Call new day here": ['#[cfg(feature = "day_{day}")]', "day_{day}::main(&input[..])?;"],
-    },
-}
 
 parser = argparse.ArgumentParser(description="Bootstraps an AoC solution")
 parser.add_argument("-v", "--verbose", action="store_true",
@@ -74,30 +65,15 @@ if __name__ == "__main__":
     if args.codegen:
         for template, dest in TEMPLATES.items():
             dest = dest.format(**args.__dict__)
-            copyfile(template, dest)
 
-            if args.verbose:
-                log(f"Copied {template} to {dest}", kind="success")
-
-        for filename, marker_inserts in INSERT_FILES.items():
-            with open(filename, "r") as f:
-                main_lines = [line.rstrip() for line in f.readlines()]
+            with open(template, "r") as f:
+                content = f.read().replace("DayN", "Day{day}".format(**args.__dict__))
 
-            for marker, inserts in marker_inserts.items():
-                for i, line in enumerate(main_lines):
-                    try:
-                        indent = line.index(marker)
-                        main_lines[i:i] = [indent * ' ' + insert.format(**args.__dict__) for insert in inserts]
-                        break
-                    except ValueError:
-                        pass
-
-            with open(filename, "w") as f:
-                for line in main_lines:
-                    print(line, file=f)
+            with open(dest, "w") as f:
+                f.write(content)
 
             if args.verbose:
-                log(f"Inserted lines into {filename}", kind="success")
+                log(f"Copied {template} to {dest}", kind="success")
 
     if args.input:
         response = requests.get(
@@ -0,0 +1,47 @@
+//! Registers every `src/day_N.rs` module, so that adding a day only takes creating its file.
+//!
+//! Generates `days.rs`, included by the library, which declares the day modules and the
+//! registry of their solutions, and `example_tests.rs`, included by the example tests.
+
+use std::{env, error::Error, fmt::Write, fs, path::Path};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let src = Path::new(&env::var("CARGO_MANIFEST_DIR")?).join("src");
+    let out = Path::new(&env::var("OUT_DIR")?).to_owned();
+
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let mut days: Vec<u32> = fs::read_dir(&src)?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_prefix("day_")?.strip_suffix(".rs")?.parse().ok()
+        })
+        .collect();
+
+    days.sort_unstable();
+
+    let mut modules = String::new();
+    for &day in &days {
+        let path = src.join(format!("day_{}.rs", day));
+        writeln!(modules, "#[path = {:?}]\npub mod day_{};", path.display().to_string(), day)?;
+    }
+
+    writeln!(modules, "\n/// All available solutions, indexed by day number")?;
+    writeln!(modules, "pub const SOLUTIONS: &[(u32, &dyn DynSolution)] = &[")?;
+    for &day in &days {
+        writeln!(modules, "    ({0}, &day_{0}::Day{0}),", day)?;
+    }
+    writeln!(modules, "];")?;
+
+    fs::write(out.join("days.rs"), modules)?;
+
+    let mut tests = String::from("example_tests! {\n");
+    for &day in &days {
+        writeln!(tests, "    day_{0}: {0},", day)?;
+    }
+    tests.push_str("}\n");
+
+    fs::write(out.join("example_tests.rs"), tests)?;
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+
+use crate::solution::Part;
+
+/// Known-correct answers, stored in a TOML-like file with one table per day:
+///
+/// ```toml
+/// [day_1]
+/// part_1 = "1502"
+/// part_2 = 1538
+/// ```
+///
+/// Values can be quoted strings or bare words. Comments start with `#`.
+#[derive(Default, Debug)]
+pub struct Answers(BTreeMap<(u32, Part), String>);
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    pub fn parse(s: &str) -> Result<Self, Box<dyn Error>> {
+        let mut answers = BTreeMap::new();
+        let mut day = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let malformed = || format!("malformed entry on line {}: `{}`", i + 1, line);
+
+            if let Some(table) = line.strip_prefix('[') {
+                let table = table.strip_suffix(']').ok_or_else(malformed)?;
+                let number = table.trim().strip_prefix("day_").ok_or_else(malformed)?;
+                day = Some(number.parse::<u32>().map_err(|_| malformed())?);
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(malformed)?;
+            let day = day.ok_or_else(|| format!("entry outside of a day table on line {}", i + 1))?;
+
+            let part = key
+                .trim()
+                .strip_prefix("part_")
+                .and_then(|part| part.parse::<Part>().ok())
+                .ok_or_else(malformed)?;
+
+            let value = value.trim();
+            let value = match value.strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"').ok_or_else(malformed)?.0,
+                None => value.split('#').next().unwrap_or("").trim(),
+            };
+
+            answers.insert((day, part), value.to_owned());
+        }
+
+        Ok(Self(answers))
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::parse(
+            "# comment\n\
+             [day_1]\n\
+             part_1 = \"1502\" # quoted\n\
+             part_2 = 1538\n\
+             \n\
+             [day_10]\n\
+             part_2 = abc\n",
+        )
+        .unwrap();
+
+        assert_eq!(answers.get(1, Part::One), Some("1502"));
+        assert_eq!(answers.get(1, Part::Two), Some("1538"));
+        assert_eq!(answers.get(10, Part::One), None);
+        assert_eq!(answers.get(10, Part::Two), Some("abc"));
+
+        assert!(Answers::parse("part_1 = 1").is_err());
+        assert!(Answers::parse("[day_x]").is_err());
+    }
+}
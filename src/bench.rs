@@ -0,0 +1,156 @@
+use std::{
+    error::Error,
+    fmt::Write,
+    fs,
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    input::InputSource,
+    solution::{DynSolution, Part},
+    table::Table,
+};
+
+/// Summary statistics of repeated timings
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Computes statistics of the given samples. Returns `None` if there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        let mean = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Some(Self {
+            runs: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Times `f` over the given number of runs
+fn measure<T, F>(runs: usize, mut f: F) -> Result<Stats, Box<dyn Error>>
+where
+    F: FnMut() -> Result<T, Box<dyn Error>>,
+{
+    let mut samples = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        black_box(f()?);
+        samples.push(start.elapsed());
+    }
+
+    Ok(Stats::from_samples(samples).ok_or("number of runs must be positive")?)
+}
+
+/// Benchmarks parsing and each part of a day's solution, printing a table of statistics.
+/// If `report` is given, the statistics are also written there as CSV.
+pub fn bench_day(
+    day: u32,
+    solution: &dyn DynSolution,
+    input: &InputSource,
+    runs: usize,
+    report: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let input = input.read()?;
+
+    let parsed = solution
+        .parse_any(&input[..])
+        .map_err(|e| e.with_day(day))?;
+
+    let parse = measure(runs, || Ok(solution.parse_any(&input[..])?))?;
+    let mut results = vec![("parse".to_owned(), parse)];
+
+    for part in Part::ALL {
+        match measure(runs, || solution.solve_any(&*parsed, part)) {
+            Ok(stats) => results.push((format!("part {}", part), stats)),
+            Err(e) => log::warn!("skipping part {}: {}", part, e),
+        }
+    }
+
+    let mut table = Table::new(["Stage", "Runs", "Min", "Median", "Mean", "Std. dev."]);
+    for (stage, stats) in &results {
+        table.row([
+            stage.clone(),
+            stats.runs.to_string(),
+            format!("{:.2?}", stats.min),
+            format!("{:.2?}", stats.median),
+            format!("{:.2?}", stats.mean),
+            format!("{:.2?}", stats.stddev),
+        ]);
+    }
+    print!("{}", table);
+
+    if let Some(report) = report {
+        let mut csv = String::from("day,stage,runs,min_ns,median_ns,mean_ns,stddev_ns\n");
+        for (stage, stats) in &results {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                day,
+                stage,
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos(),
+            )?;
+        }
+        fs::write(report, csv)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples() {
+        let ms = |samples: &[u64]| samples.iter().map(|&ms| Duration::from_millis(ms)).collect::<Vec<_>>();
+        let close = |a: Duration, b: f64| (a.as_secs_f64() * 1000.0 - b).abs() < 1e-6;
+
+        let odd = Stats::from_samples(ms(&[3, 1, 2])).unwrap();
+        assert_eq!((odd.runs, odd.min, odd.median), (3, Duration::from_millis(1), Duration::from_millis(2)));
+        assert!(close(odd.mean, 2.0));
+        assert!(close(odd.stddev, (2.0f64 / 3.0).sqrt()));
+
+        let even = Stats::from_samples(ms(&[4, 1, 3, 2])).unwrap();
+        assert_eq!((even.runs, even.min), (4, Duration::from_millis(1)));
+        assert_eq!(even.median, Duration::from_micros(2500));
+        assert!(close(even.mean, 2.5));
+        assert!(close(even.stddev, 1.25f64.sqrt()));
+
+        assert!(Stats::from_samples(vec![]).is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! Arbitrary-precision unsigned integers, just enough for counting things that grow exponentially
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Mul},
+};
+
+/// An unsigned integer of any size
+#[derive(Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first, without trailing zeros
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn trim(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// Returns the nearest floating point number, or infinity if it's too large
+    pub fn to_f64(&self) -> f64 {
+        self.limbs
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &limb| acc * 4_294_967_296.0 + limb as f64)
+    }
+
+    /// Returns the base 2 logarithm, or negative infinity for zero. Unlike the logarithm of
+    /// [`Self::to_f64`], it's finite for numbers too large for floating point.
+    pub fn log2(&self) -> f64 {
+        match self.limbs[..] {
+            [] => f64::NEG_INFINITY,
+            [low] => (low as f64).log2(),
+            [.., next, top] => {
+                let shift = 32 * (self.limbs.len() - 2);
+                (top as f64 * 4_294_967_296.0 + next as f64).log2() + shift as f64
+            }
+        }
+    }
+
+    /// Divides in place by a small divisor, returning the remainder
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+
+        for limb in self.limbs.iter_mut().rev() {
+            let acc = (rem << 32) | *limb as u64;
+            *limb = (acc / divisor as u64) as u32;
+            rem = acc % divisor as u64;
+        }
+
+        *self = std::mem::take(self).trim();
+        rem as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        Self {
+            limbs: vec![n as u32, (n >> 32) as u32],
+        }
+        .trim()
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = match self.limbs.len() >= other.limbs.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u64;
+
+        for (i, &limb) in long.limbs.iter().enumerate() {
+            let sum = limb as u64 + *short.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+
+        limbs.push(carry as u32);
+        BigUint { limbs }.trim()
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let acc = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = acc as u32;
+                carry = acc >> 32;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.trim()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        let mut n = self.clone();
+        let mut chunks = vec![];
+
+        while !n.is_zero() {
+            chunks.push(n.div_rem_small(CHUNK));
+        }
+
+        let mut digits = match chunks.pop() {
+            Some(first) => first.to_string(),
+            None => "0".to_owned(),
+        };
+
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{:09}", chunk);
+        }
+
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = BigUint::from(u64::MAX);
+        let b = &a + &BigUint::from(1);
+        assert_eq!(b.to_string(), "18446744073709551616");
+
+        let square = &b * &b;
+        assert_eq!(square.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(square.to_f64(), 2f64.powi(128));
+        assert_eq!(square.log2(), 128.0);
+        assert_eq!(BigUint::from(8).log2(), 3.0);
+        assert_eq!(BigUint::zero().log2(), f64::NEG_INFINITY);
+
+        assert!(square > b && b > a);
+        assert_eq!(&a * &BigUint::zero(), BigUint::zero());
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(format!("{:>5}", BigUint::from(42)), "   42");
+    }
+}
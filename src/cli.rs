@@ -0,0 +1,385 @@
+use std::{error::Error, path::PathBuf, time::Duration};
+
+use log::LevelFilter;
+
+use aoc_2021::{
+    image::Export,
+    input::{InputSource, INPUTS_DIR},
+    runner::Format,
+    solution::{Params, Part},
+    viz::Playback,
+};
+
+const USAGE: &str = "\
+Usage:
+    aoc-2021 [-v | -vv | --quiet] <COMMAND> ...
+
+    aoc-2021 run --day <N> [--part <1|2>] [--format <text|json>] [--example | <INPUT> | --input-str <TEXT>]
+                 [--visualize [--fps <FPS>] [--paused]]
+                 [--export <IMAGE> [--palette <PALETTE>] [--scale <PX>] [--delay <MS>]]
+                 [--report <NAME> | --svg] [--set <KEY>=<VALUE>]...
+    aoc-2021 all [--example] [--format <text|json>]
+    aoc-2021 bench --day <N> [--runs <RUNS>] [--report <CSV>] [--example | <INPUT> | --input-str <TEXT>]
+    aoc-2021 verify [--day <N>] [--answers <FILE>] [--example]
+    aoc-2021 new --day <N>
+    aoc-2021 fetch [--year <Y>] --day <N> [--base-url <URL>]
+    aoc-2021 examples [--year <Y>] --day <N> [--page <HTML> | --base-url <URL>] [--save <K>]
+
+`-v` prints debug diagnostics of solutions to the standard error, `-vv` prints everything
+down to traces, and `--quiet` only prints errors.
+
+Without an <INPUT>, the input is read from `inputs/day<N>.txt`, or from
+`inputs/day<N>-example.txt` with `--example`. An <INPUT> of `-` reads the standard input.
+
+`verify` compares the answers with the known-correct ones in `answers.toml`, or with
+`--example` in `inputs/examples.toml`, unless `--answers` is given.
+
+`--visualize` plays back the frames emitted while solving, at `--fps` frames per second
+(4 by default). Playback can be paused with space, stepped through with `n` and `b`,
+sped up or slowed down with `+` and `-`, and quit with `q`.
+
+`--export` saves the grid frames emitted while solving as a `.gif` animation, or the last
+one as a `.png` or `.ppm` image, with cells of `--scale` pixels (4 by default) shown for
+`--delay` milliseconds each (100 by default). A <PALETTE> is one of gray, heat (the default),
+ocean and viridis, or a list of comma-separated `#rrggbb` colours from low to high values.
+
+`--report` prints a day-specific report on the input instead of the answers, e.g. `svg`
+for day 5, which `--svg` is short for:
+
+    day 5    svg                      segments coloured by orientation, with overlaps marked
+    day 6    series, series-json,     number of fish with each timer on each day as CSV
+             growth                   or JSON, and statistics of the population's growth
+    day 7    curve, plot, optimum     total fuel cost by position as CSV or an ASCII plot,
+                                      and the optimal positions with fuel spent per crab
+
+`--set` passes a setting to the solution and its reports, e.g. `--set cost=quadratic`:
+
+    day 6    days                     days to simulate in both parts and the reports (80 and
+                                      256 by default in part 1 and elsewhere)
+             modulus                  count fish modulo this number, which allows simulating
+                                      more than 100000 days
+             reset, newborn           timers of a fish after giving birth and of a newborn
+                                      (6 and 8 by default)
+             species                  several species simulated side by side, each starting
+                                      with the input's fish, as comma-separated timers
+                                      `<reset>/<newborn>`, e.g. `6/8,2/4`
+    day 7    cost                     fuel cost model of part 2 and the reports, linear,
+                                      triangular (the default) or quadratic";
+
+/// A parsed command line invocation
+pub(crate) enum Command {
+    /// Runs a single day, either one part or both parts
+    Run {
+        day: u32,
+        part: Option<Part>,
+        input: InputSource,
+        format: Format,
+        /// Plays back the frames emitted while solving, if set
+        visualize: Option<Playback>,
+        /// Saves the frames emitted while solving to an image, if set
+        export: Option<Export>,
+        /// Prints the named report instead of the answers, if set
+        report: Option<String>,
+        /// Settings of the solution and its reports
+        params: Params,
+    },
+    /// Runs every day on its input from `inputs/`
+    All { example: bool, format: Format },
+    /// Times parsing and both parts of a single day over repeated runs
+    Bench {
+        day: u32,
+        input: InputSource,
+        runs: usize,
+        report: Option<PathBuf>,
+    },
+    /// Checks answers of all days (or a single day) against the known-correct ones
+    Verify {
+        day: Option<u32>,
+        answers: PathBuf,
+        example: bool,
+    },
+    /// Creates the module for a new day from the template
+    New { day: u32 },
+    /// Downloads a day's input into `inputs/`, unless it's already there
+    Fetch {
+        year: u32,
+        day: u32,
+        base_url: Option<String>,
+    },
+    /// Lists the code blocks of a puzzle page, optionally saving one as an example input
+    Examples {
+        year: u32,
+        day: u32,
+        page: Option<PathBuf>,
+        base_url: Option<String>,
+        save: Option<usize>,
+    },
+}
+
+impl Command {
+    pub(crate) fn parse<I>(args: I) -> Result<Self, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+
+        match args.next().as_deref() {
+            Some("run") => {
+                let mut day = None;
+                let mut part = None;
+                let mut input = None;
+                let mut example = false;
+                let mut format = Format::Text;
+                let mut visualize = false;
+                let mut playback = Playback::default();
+                let mut export = None;
+                let mut palette = None;
+                let mut scale = None;
+                let mut delay = None;
+                let mut report = None;
+                let mut params = Params::new();
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-d" | "--day" => day = Some(value(&mut args, &arg)?.parse()?),
+                        "-p" | "--part" => part = Some(value(&mut args, &arg)?.parse()?),
+                        "-f" | "--format" => format = value(&mut args, &arg)?.parse()?,
+                        "--visualize" => visualize = true,
+                        "--fps" => playback.fps = value(&mut args, &arg)?.parse()?,
+                        "--paused" => playback.paused = true,
+                        "--export" => export = Some(PathBuf::from(value(&mut args, &arg)?)),
+                        "--palette" => palette = Some(value(&mut args, &arg)?.parse()?),
+                        "--scale" => scale = Some(value(&mut args, &arg)?.parse()?),
+                        "--delay" => delay = Some(Duration::from_millis(value(&mut args, &arg)?.parse()?)),
+                        "--report" => report = Some(value(&mut args, &arg)?),
+                        "--svg" => report = Some("svg".to_owned()),
+                        "--set" => params.extend(value(&mut args, &arg)?.parse::<Params>()?),
+                        "--input-str" => input = Some(InputSource::Inline(value(&mut args, &arg)?)),
+                        "-e" | "--example" => example = true,
+                        _ if arg.starts_with('-') && arg != "-" => {
+                            return Err(format!("unknown option `{}`\n\n{}", arg, USAGE).into())
+                        }
+                        _ => input = Some(InputSource::from_arg(&arg)),
+                    }
+                }
+
+                let day = day.ok_or(format!("missing `--day`\n\n{}", USAGE))?;
+
+                let export = export.map(|path| {
+                    let mut export = Export::new(path);
+                    if let Some(palette) = palette {
+                        export.palette = palette;
+                    }
+                    export.scale = scale.unwrap_or(export.scale);
+                    export.delay = delay.unwrap_or(export.delay);
+                    export
+                });
+
+                Ok(Self::Run {
+                    day,
+                    part,
+                    input: input.unwrap_or(InputSource::Day { day, example }),
+                    format,
+                    visualize: visualize.then_some(playback),
+                    export,
+                    report,
+                    params,
+                })
+            }
+            Some("all") => {
+                let mut example = false;
+                let mut format = Format::Text;
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-e" | "--example" => example = true,
+                        "-f" | "--format" => format = value(&mut args, &arg)?.parse()?,
+                        _ => return Err(format!("unexpected argument `{}`\n\n{}", arg, USAGE).into()),
+                    }
+                }
+
+                Ok(Self::All { example, format })
+            }
+            Some("bench") => {
+                let mut day = None;
+                let mut input = None;
+                let mut example = false;
+                let mut runs = 100;
+                let mut report = None;
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-d" | "--day" => day = Some(value(&mut args, &arg)?.parse()?),
+                        "-n" | "--runs" => runs = value(&mut args, &arg)?.parse()?,
+                        "-r" | "--report" => report = Some(PathBuf::from(value(&mut args, &arg)?)),
+                        "--input-str" => input = Some(InputSource::Inline(value(&mut args, &arg)?)),
+                        "-e" | "--example" => example = true,
+                        _ if arg.starts_with('-') && arg != "-" => {
+                            return Err(format!("unknown option `{}`\n\n{}", arg, USAGE).into())
+                        }
+                        _ => input = Some(InputSource::from_arg(&arg)),
+                    }
+                }
+
+                let day = day.ok_or(format!("missing `--day`\n\n{}", USAGE))?;
+
+                Ok(Self::Bench {
+                    day,
+                    input: input.unwrap_or(InputSource::Day { day, example }),
+                    runs,
+                    report,
+                })
+            }
+            Some("verify") => {
+                let mut day = None;
+                let mut answers = None;
+                let mut example = false;
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-d" | "--day" => day = Some(value(&mut args, &arg)?.parse()?),
+                        "-a" | "--answers" => answers = Some(PathBuf::from(value(&mut args, &arg)?)),
+                        "-e" | "--example" => example = true,
+                        _ => return Err(format!("unexpected argument `{}`\n\n{}", arg, USAGE).into()),
+                    }
+                }
+
+                // Example answers are kept next to the example inputs
+                let answers = answers.unwrap_or_else(|| match example {
+                    true => PathBuf::from(INPUTS_DIR).join("examples.toml"),
+                    false => PathBuf::from("answers.toml"),
+                });
+
+                Ok(Self::Verify { day, answers, example })
+            }
+            Some("new") => {
+                let mut day = None;
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-d" | "--day" => day = Some(value(&mut args, &arg)?.parse()?),
+                        _ => return Err(format!("unexpected argument `{}`\n\n{}", arg, USAGE).into()),
+                    }
+                }
+
+                Ok(Self::New {
+                    day: day.ok_or(format!("missing `--day`\n\n{}", USAGE))?,
+                })
+            }
+            Some("fetch") => {
+                let mut year = 2021;
+                let mut day = None;
+                let mut base_url = None;
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-y" | "--year" => year = value(&mut args, &arg)?.parse()?,
+                        "-d" | "--day" => day = Some(value(&mut args, &arg)?.parse()?),
+                        "--base-url" => base_url = Some(value(&mut args, &arg)?),
+                        _ => return Err(format!("unexpected argument `{}`\n\n{}", arg, USAGE).into()),
+                    }
+                }
+
+                Ok(Self::Fetch {
+                    year,
+                    day: day.ok_or(format!("missing `--day`\n\n{}", USAGE))?,
+                    base_url,
+                })
+            }
+            Some("examples") => {
+                let mut year = 2021;
+                let mut day = None;
+                let mut page = None;
+                let mut base_url = None;
+                let mut save = None;
+
+                while let Some(arg) = args.next() {
+                    match &arg[..] {
+                        "-y" | "--year" => year = value(&mut args, &arg)?.parse()?,
+                        "-d" | "--day" => day = Some(value(&mut args, &arg)?.parse()?),
+                        "--page" => page = Some(PathBuf::from(value(&mut args, &arg)?)),
+                        "--base-url" => base_url = Some(value(&mut args, &arg)?),
+                        "-s" | "--save" => save = Some(value(&mut args, &arg)?.parse()?),
+                        _ => return Err(format!("unexpected argument `{}`\n\n{}", arg, USAGE).into()),
+                    }
+                }
+
+                Ok(Self::Examples {
+                    year,
+                    day: day.ok_or(format!("missing `--day`\n\n{}", USAGE))?,
+                    page,
+                    base_url,
+                    save,
+                })
+            }
+            Some(cmd) => Err(format!("unknown command `{}`\n\n{}", cmd, USAGE).into()),
+            None => Err(USAGE.into()),
+        }
+    }
+}
+
+/// Removes the global verbosity options preceding the command from the arguments, returning
+/// the selected level. Arguments from the command on are left alone, even if they look like
+/// verbosity options, since they may be values of the command's options.
+pub(crate) fn verbosity(args: &mut Vec<String>) -> LevelFilter {
+    let mut level = LevelFilter::Info;
+    let mut taken = 0;
+
+    for arg in args.iter() {
+        level = match &arg[..] {
+            "-v" | "--verbose" => level.max(LevelFilter::Debug),
+            "-vv" => LevelFilter::Trace,
+            "-q" | "--quiet" => LevelFilter::Error,
+            _ => break,
+        };
+        taken += 1;
+    }
+
+    args.drain(..taken);
+    level
+}
+
+/// Takes the value following the given option
+fn value<I>(args: &mut I, option: &str) -> Result<String, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    args.next()
+        .ok_or_else(|| format!("missing value for `{}`", option).into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|&arg| arg.to_owned()).collect()
+    }
+
+    #[test]
+    fn verbosity_before_command() {
+        let mut run = args(&["-v", "run", "--day", "10", "--input-str", "-q"]);
+        assert_eq!(verbosity(&mut run), LevelFilter::Debug);
+        assert_eq!(run, args(&["run", "--day", "10", "--input-str", "-q"]));
+
+        let mut quiet = args(&["--quiet", "-vv", "all"]);
+        assert_eq!(verbosity(&mut quiet), LevelFilter::Trace);
+        assert_eq!(quiet, args(&["all"]));
+
+        let mut none = args(&["run", "-v"]);
+        assert_eq!(verbosity(&mut none), LevelFilter::Info);
+        assert_eq!(none.len(), 2);
+    }
+
+    #[test]
+    fn verify_answers_default() {
+        let answers = |list: &[&str]| match Command::parse(args(list)).unwrap() {
+            Command::Verify { answers, .. } => answers,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(answers(&["verify"]), PathBuf::from("answers.toml"));
+        assert_eq!(answers(&["verify", "--example"]), PathBuf::from("inputs/examples.toml"));
+        assert_eq!(answers(&["verify", "--example", "--answers", "a.toml"]), PathBuf::from("a.toml"));
+    }
+}
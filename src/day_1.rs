@@ -1,33 +1,35 @@
 use std::error::Error;
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    let ns: Vec<u32> = input
-        .lines()
-        .map(|line| line.parse())
-        .collect::<Result<_, _>>()?;
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
+};
 
-    #[cfg(feature = "part_1")]
-    {
-        let result = ns
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input).map(|line| line.parse("a depth")).collect()
+    }
+
+    fn part_one(ns: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        Ok(ns
             .array_windows() // ns.iter().zip(ns.iter().skip(1))
             .filter(|[a, b]| a < b)
-            .count();
-
-        println!("{:?}", result);
+            .count())
     }
 
-    #[cfg(feature = "part_2")]
-    {
-        let result = ns
+    fn part_two(ns: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        Ok(ns
             .array_windows()
             .map(|[a, b, c]| a + b + c)
             .collect::<Vec<_>>()
             .array_windows()
             .filter(|[a, b]| a < b)
-            .count();
-
-        println!("{:?}", result);
+            .count())
     }
-
-    Ok(())
 }
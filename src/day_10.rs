@@ -1,6 +1,12 @@
 use std::error::Error;
 
-fn closing(opening: char) -> Option<char> {
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
+};
+
+/// Returns the closing counterpart of an opening bracket
+pub fn closing(opening: char) -> Option<char> {
     Some(match opening {
         '(' => ')',
         '[' => ']',
@@ -10,7 +16,9 @@ fn closing(opening: char) -> Option<char> {
     })
 }
 
-fn validate(line: &str) -> (Option<char>, Option<String>) {
+/// Checks the brackets in the line. Returns the first illegal closing bracket if the line is
+/// corrupted, or the sequence of brackets that would complete it if it's incomplete.
+pub fn validate(line: &str) -> (Option<char>, Option<String>) {
     let mut stack = vec![];
 
     for ch in line.chars() {
@@ -41,13 +49,25 @@ fn validate(line: &str) -> (Option<char>, Option<String>) {
     )
 }
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    let lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+            .map(|line| match line.find_invalid(line.text, |ch| "()[]{}<>".contains(ch)) {
+                Some(ch) => Err(line.error(ch, "expected a bracket")),
+                None => Ok(line.text.to_owned()),
+            })
+            .collect()
+    }
 
-    #[cfg(feature = "part_1")]
-    {
-        let result: u64 = lines
-            .clone()
+    fn part_one(lines: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        Ok(lines
+            .iter()
             .filter_map(|line| validate(line).0)
             .map(|ch| match ch {
                 ')' => 3,
@@ -56,15 +76,12 @@ pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
                 '>' => 25137,
                 _ => 0,
             })
-            .sum();
-
-        println!("{}", result);
+            .sum())
     }
 
-    #[cfg(feature = "part_2")]
-    {
+    fn part_two(lines: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
         let mut results: Vec<_> = lines
-            .clone()
+            .iter()
             .filter_map(|line| validate(line).1)
             .map(|comp| {
                 comp.chars()
@@ -81,10 +98,6 @@ pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
 
         results.sort();
 
-        let med = results[results.len() / 2];
-
-        println!("{}", med);
+        Ok(*results.get(results.len() / 2).ok_or("no incomplete lines")?)
     }
-
-    Ok(())
 }
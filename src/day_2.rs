@@ -1,33 +1,61 @@
 use std::error::Error;
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    let mut x = 0;
-    let mut depth = 0;
-    #[cfg(feature = "part_2")]
-    let mut aim = 0;
-
-    for line in input.lines() {
-        let (dir, steps) = line.split_once(" ").unwrap();
-        let steps = steps.parse::<i64>()?;
-
-        #[cfg(feature = "part_1")]
-        match dir {
-            "forward" => x += steps,
-            "up" => depth -= steps,
-            "down" => depth += steps,
-            _ => (),
-        }
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<(String, i64)>;
+    type Answer1 = Answer;
+    type Answer2 = Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+            .map(|line| {
+                let (dir, steps) = line.command("a number of steps")?;
+
+                if !["forward", "up", "down"].contains(&dir) {
+                    return Err(line.error(dir, "expected `forward`, `up` or `down`"));
+                }
+
+                Ok((dir.to_owned(), steps))
+            })
+            .collect()
+    }
+
+    fn part_one(commands: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        let mut x = 0;
+        let mut depth = 0;
 
-        #[cfg(feature = "part_2")]
-        match dir {
-            "forward" => { x += steps; depth += steps * aim; }
-            "up" => aim -= steps,
-            "down" => aim += steps,
-            _ => (),
+        for (dir, steps) in commands {
+            match &dir[..] {
+                "forward" => x += steps,
+                "up" => depth -= steps,
+                "down" => depth += steps,
+                _ => (),
+            }
         }
+
+        Ok(Answer::new(x * depth).with("x", x).with("depth", depth))
     }
 
-    println!("x = {}; depth = {}; x * depth = {}", x, depth, x * depth);
+    fn part_two(commands: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        let mut x = 0;
+        let mut depth = 0;
+        let mut aim = 0;
 
-    Ok(())
+        for (dir, steps) in commands {
+            match &dir[..] {
+                "forward" => { x += steps; depth += steps * aim; }
+                "up" => aim -= steps,
+                "down" => aim += steps,
+                _ => (),
+            }
+        }
+
+        Ok(Answer::new(x * depth).with("x", x).with("depth", depth))
+    }
 }
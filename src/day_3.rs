@@ -1,5 +1,10 @@
 use std::error::Error;
 
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
+
 fn bit_sums<I, L>(lines: I) -> Vec<i32>
 where
     I: IntoIterator<Item = L>,
@@ -43,23 +48,37 @@ where
         .collect()
 }
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    #[cfg(feature = "part_1")]
-    {
-        let sums = bit_sums(input.lines());
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<String>;
+    type Answer1 = Answer;
+    type Answer2 = Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let numbers: Vec<String> = parse::char_grid(input, "a binary digit", |ch| matches!(ch, '0' | '1').then_some(ch))?
+            .into_iter()
+            .map(String::from_iter)
+            .collect();
+
+        if numbers.is_empty() {
+            return Err(ParseError::input("expected at least one number"));
+        }
+
+        Ok(numbers)
+    }
+
+    fn part_one(lines: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        let sums = bit_sums(lines);
         let gamma = u32::from_str_radix(&gamma_str(&sums)[..], 2)?;
         let epsilon = u32::from_str_radix(&epsilon_str(&sums)[..], 2)?;
 
-        println!(
-            "gamma = {}; epsilon = {}; gamma * epsilon = {}",
-            gamma, epsilon, gamma * epsilon,
-        );
+        Ok(Answer::new(gamma * epsilon).with("gamma", gamma).with("epsilon", epsilon))
     }
 
-    #[cfg(feature = "part_2")]
-    {
-        let length = input.lines().next().unwrap().len();
-        let mut gamma_sieve = input.lines().collect::<Vec<_>>();
+    fn part_two(lines: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        let length = lines.first().ok_or("empty input")?.len();
+        let mut gamma_sieve = lines.iter().map(String::as_str).collect::<Vec<_>>();
         let mut epsilon_sieve = gamma_sieve.clone();
 
         for i in 0..length {
@@ -78,8 +97,6 @@ pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
         let ogr = u32::from_str_radix(gamma_sieve.first().unwrap(), 2)?;
         let csr = u32::from_str_radix(epsilon_sieve.first().unwrap(), 2)?;
 
-        println!("ogr = {}, csr = {}, ogr * csr = {}", ogr, csr, ogr * csr);
+        Ok(Answer::new(ogr * csr).with("ogr", ogr).with("csr", csr))
     }
-
-    Ok(())
 }
@@ -3,14 +3,22 @@ use std::{
     fmt::{self, Write},
 };
 
+use crate::{
+    grid::Grid,
+    parse::{self, ParseError},
+    solution::Solution,
+    viz::{self, Frame},
+};
+
+/// A single number on a bingo board
 #[derive(Clone)]
-struct Cell {
+pub struct Cell {
     value: u32,
     checked: bool,
 }
 
 impl Cell {
-    fn new(value: u32) -> Self {
+    pub fn new(value: u32) -> Self {
         Self {
             value,
             checked: false,
@@ -18,39 +26,27 @@ impl Cell {
     }
 }
 
-struct Board<const N: usize> {
-    cells: [[Cell; N]; N],
+/// A square bingo board of size `N`
+#[derive(Clone)]
+pub struct Board<const N: usize> {
+    cells: Grid<Cell>,
     won: bool,
 }
 
 impl<const N: usize> Board<N> {
-    fn new<B, R>(cells: B) -> Self
-    where
-        B: AsRef<[R]>,
-        R: AsRef<[Cell]>,
-    {
-        Self {
-            cells: TryInto::<&[[Cell; N]; N]>::try_into(
-                cells
-                    .as_ref()
-                    .iter()
-                    .map(|row| {
-                        TryInto::<&[Cell; N]>::try_into(row.as_ref())
-                            .unwrap()
-                            .clone()
-                    })
-                    .collect::<Vec<_>>()
-                    .into_boxed_slice()
-                    .as_ref(),
-            )
-            .unwrap()
-            .clone(),
-            won: false,
-        }
+    /// Constructs a board from a grid of cells.
+    ///
+    /// # Panics
+    /// Panics if the cells don't form an `N` by `N` square.
+    pub fn new(cells: Grid<Cell>) -> Self {
+        assert!(cells.width() == N && cells.height() == N, "a board must be {0} by {0} cells", N);
+        Self { cells, won: false }
     }
 
-    fn draw(&mut self, number: u32) -> Option<u32> {
-        for cell in self.cells.iter_mut().flat_map(|row| row.iter_mut()) {
+    /// Checks cells with the drawn number. Returns the final score if this draw made
+    /// the board win for the first time.
+    pub fn draw(&mut self, number: u32) -> Option<u32> {
+        for cell in self.cells.iter_mut() {
             if cell.value == number {
                 cell.checked = true;
             }
@@ -64,37 +60,25 @@ impl<const N: usize> Board<N> {
         }
     }
 
-    fn is_winning(&self) -> bool {
-        for row in 0..N {
-            if self.cells[row].iter().all(|cell| cell.checked) {
-                return true;
-            }
-        }
-
-        for col in 0..N {
-            if self.cells.iter().all(|row| row[col].checked) {
-                return true;
-            }
-        }
-
-        false
+    /// Returns `true` if any full row or column is checked
+    pub fn is_winning(&self) -> bool {
+        self.cells.rows().any(|row| row.iter().all(|cell| cell.checked))
+            || self.cells.columns().any(|mut col| col.all(|cell| cell.checked))
     }
 
-    fn score(&self) -> u32 {
+    /// Returns the sum of all unchecked numbers
+    pub fn score(&self) -> u32 {
         self.cells
             .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| if !cell.checked { cell.value } else { 0 })
-                    .sum::<u32>()
-            })
+            .filter(|cell| !cell.checked)
+            .map(|cell| cell.value)
             .sum()
     }
 }
 
 impl<const N: usize> fmt::Display for Board<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.cells {
+        for row in self.cells.rows() {
             for cell in row {
                 if cell.checked {
                     f.write_fmt(format_args!("\x1b[90m{:>2}\x1b[0m ", cell.value))?;
@@ -108,68 +92,99 @@ impl<const N: usize> fmt::Display for Board<N> {
     }
 }
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    const BOARD_SIZE: usize = 5;
+pub const BOARD_SIZE: usize = 5;
 
-    let mut lines = input.lines();
+pub struct Day4;
 
-    let draws = lines.next().unwrap().split(',').map(str::parse);
+impl Day4 {
+    /// Plays bingo until the first board wins, or until all boards have won if `until_last`
+    /// is set. Returns the score of the last board to win.
+    fn play(draws: &[u32], boards: &[Board<BOARD_SIZE>], until_last: bool) -> Option<u32> {
+        let mut boards = boards.to_vec();
+        let mut last_win = None;
 
-    let mut boards: Vec<Vec<Vec<Cell>>> = vec![];
+        for &draw in draws {
+            log::debug!("draw {}", draw);
 
-    for line in lines {
-        if line.is_empty() {
-            boards.push(vec![]);
-        } else {
-            boards.last_mut().unwrap().push(
-                line.split_ascii_whitespace()
-                    .map(|s| s.parse().map(Cell::new))
-                    .collect::<Result<_, _>>()?,
-            );
+            for board in &mut boards {
+                if let Some(score) = board.draw(draw) {
+                    log::trace!("\n{}", board);
+                    log::debug!("board won, score = {}", score);
+
+                    if !until_last {
+                        return Some(score);
+                    }
+
+                    last_win = Some(score);
+                } else {
+                    log::trace!("\n{}", board);
+                }
+            }
+
+            viz::emit(|| {
+                let text = boards.iter().map(Board::to_string).collect::<Vec<_>>().join("\n");
+                Frame::text(format!("draw {}, {} boards in play", draw, boards.len()), text)
+            });
+
+            boards.retain(|board| !board.won);
+
+            if boards.is_empty() {
+                break;
+            }
         }
+
+        last_win
     }
+}
+
+impl Solution for Day4 {
+    type Input = (Vec<u32>, Vec<Board<BOARD_SIZE>>);
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    let mut boards: Vec<Board<BOARD_SIZE>> = boards
-        .into_iter()
-        .map(Board::new)
-        .collect();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let sections = parse::sections(input);
+        let (draws, boards) = sections.split_first().ok_or_else(|| ParseError::input("expected draws"))?;
 
-    #[cfg(feature = "part_2")]
-    let mut last_win = None;
+        let draws = match &draws[..] {
+            [line] => line.list(",", "a number")?,
+            [_, extra, ..] => return Err(extra.error(extra.text, "expected a blank line after the draws")),
+            [] => unreachable!(),
+        };
 
-    'outer: for draw in draws {
-        let draw = draw?;
-        println!("=== draw {} ===\n", draw);
+        let boards = boards
+            .iter()
+            .map(|lines| {
+                if lines.len() != BOARD_SIZE {
+                    let last = lines[lines.len().min(BOARD_SIZE + 1) - 1];
+                    return Err(last.error(last.text, format!("expected {} rows in a board", BOARD_SIZE)));
+                }
 
-        for board in &mut boards {
-            if let Some(score) = board.draw(draw) {
-                println!("{}", board);
-                println!("won, score = {}\n", score);
+                let rows = lines
+                    .iter()
+                    .map(|line| {
+                        let row: Vec<u32> = line.fields(line.text, "a number")?;
 
-                #[cfg(feature = "part_1")]
-                break 'outer;
+                        if row.len() != BOARD_SIZE {
+                            return Err(line.error(line.text, format!("expected {} numbers in a row", BOARD_SIZE)));
+                        }
 
-                #[cfg(feature = "part_2")]
-                { last_win = Some(score); }
-            } else {
-                println!("{}", board);
-            }
-        }
+                        Ok(row.into_iter().map(Cell::new))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
 
-        #[cfg(feature = "part_2")]
-        {
-            boards.retain(|board| !board.won);
+                Ok(Board::new(Grid::from_rows(rows)))
+            })
+            .collect::<Result<_, _>>()?;
 
-            if boards.is_empty() {
-                break 'outer;
-            }
-        }
+        Ok((draws, boards))
     }
 
-    #[cfg(feature = "part_2")]
-    if let Some(last_win) = last_win {
-        println!("score(last_to_win) = {}", last_win);
+    fn part_one((draws, boards): &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        Ok(Self::play(draws, boards, false).ok_or("no board won")?)
     }
 
-    Ok(())
+    fn part_two((draws, boards): &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        Ok(Self::play(draws, boards, true).ok_or("no board won")?)
+    }
 }
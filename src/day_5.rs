@@ -1,46 +1,243 @@
-use std::{error::Error, ops::Add, str::FromStr};
+use std::{error::Error, fmt::Write};
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    const SIZE: usize = 1000;
-    let mut grid = vec![0; SIZE * SIZE].into_boxed_slice();
+use crate::{
+    grid::Grid,
+    parse::{self, ParseError},
+    solution::{Params, Solution},
+    viz::{self, Frame},
+};
 
-    for (i, line) in input.lines().enumerate() {
-        let (a, b) = line.split_once(" -> ").ok_or("malformed input")?;
-        let a = a.split_once(',').ok_or("malformed input")?;
-        let b = b.split_once(',').ok_or("malformed input")?;
+/// Maximum coordinate (exclusive) of segment endpoints
+pub const SIZE: usize = 1000;
 
-        let a: (usize, usize) = (a.0.parse()?, a.1.parse()?);
-        let b: (usize, usize) = (b.0.parse()?, b.1.parse()?);
+/// Maximum number of frames showing segments being drawn, so that large inputs
+/// don't record a copy of the whole grid for every segment
+const MAX_FRAMES: usize = 50;
 
-        if a.0 > SIZE || a.1 > SIZE || b.0 > SIZE || b.1 > SIZE {
-            panic!("Max size ({}) exceeded on line {}", SIZE, i);
+/// A line segment given by its two endpoints
+pub type Segment = ((usize, usize), (usize, usize));
+
+/// Direction of a segment
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+    Diagonal,
+}
+
+impl Orientation {
+    pub fn of(&(a, b): &Segment) -> Self {
+        if a.1 == b.1 {
+            Orientation::Horizontal
+        } else if a.0 == b.0 {
+            Orientation::Vertical
+        } else {
+            Orientation::Diagonal
         }
+    }
 
-        #[cfg(feature = "part_1")]
-        if a.0 != b.0 && a.1 != b.1 { continue }
+    /// Colour of segments with this orientation in the SVG report
+    fn color(self) -> &'static str {
+        match self {
+            Orientation::Horizontal => "#4e79a7",
+            Orientation::Vertical => "#59a14f",
+            Orientation::Diagonal => "#f28e2b",
+        }
+    }
+}
 
-        let step = (
-            (b.0 as isize - a.0 as isize).signum(),
-            (b.1 as isize - a.1 as isize).signum(),
-        );
+pub struct Day5;
 
-        println!("Line from {:?} to {:?} step {:?}", a, b, step);
+impl Day5 {
+    /// Counts the points where at least two segments overlap (only horizontal and vertical ones,
+    /// unless `diagonals` is set)
+    fn overlaps(segments: &[Segment], diagonals: bool) -> usize {
+        Self::rasterize(segments, diagonals)
+            .iter()
+            .filter(|&&cell| cell >= 2)
+            .count()
+    }
 
-        let mut pos = a;
-        loop {
-            grid[pos.1 * SIZE + pos.0] += 1;
+    /// Counts the segments covering each point (only horizontal and vertical ones,
+    /// unless `diagonals` is set)
+    fn rasterize(segments: &[Segment], diagonals: bool) -> Grid<u32> {
+        let mut grid = Grid::new(SIZE, SIZE, 0u32);
+        let (width, height) = Self::extent(segments);
+        let stride = segments.len().div_ceil(MAX_FRAMES);
 
-            if pos == b { break }
+        for (i, &(a, b)) in segments.iter().enumerate() {
+            if !diagonals && Orientation::of(&(a, b)) == Orientation::Diagonal { continue }
 
-            pos = (
-                pos.0.saturating_add_signed(step.0),
-                pos.1.saturating_add_signed(step.1),
+            let step = (
+                (b.0 as isize - a.0 as isize).signum(),
+                (b.1 as isize - a.1 as isize).signum(),
             );
+
+            log::trace!("line from {:?} to {:?} step {:?}", a, b, step);
+
+            let mut pos = a;
+            let mut line = vec![];
+            loop {
+                grid[pos] += 1;
+                line.push(pos);
+
+                if pos == b { break }
+
+                pos = (
+                    pos.0.saturating_add_signed(step.0),
+                    pos.1.saturating_add_signed(step.1),
+                );
+            }
+
+            if (i + 1) % stride == 0 {
+                viz::emit(|| {
+                    Frame::grid(format!("line from {:?} to {:?}", a, b), grid.crop(0, 0, width, height))
+                        .with_blank(0)
+                        .with_highlights(line)
+                });
+            }
         }
+
+        viz::emit(|| Frame::grid("overlap density", grid.crop(0, 0, width, height)).with_blank(0));
+
+        grid
+    }
+
+    /// Draws the segments as lines coloured by orientation, marking the points where
+    /// at least two of them overlap
+    fn svg(segments: &[Segment]) -> Result<String, std::fmt::Error> {
+        let (width, height) = Self::extent(segments);
+        let stroke = (width.max(height) as f64 / 400.0).max(0.15);
+
+        let mut svg = String::new();
+        writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}">"#, width, height)?;
+        writeln!(svg, r##"<rect width="100%" height="100%" fill="#101418"/>"##)?;
+
+        writeln!(svg, r#"<g stroke-width="{}" stroke-linecap="round">"#, stroke)?;
+        for segment @ &(a, b) in segments {
+            writeln!(
+                svg,
+                r#"<line x1="{}.5" y1="{}.5" x2="{}.5" y2="{}.5" stroke="{}"><title>{},{} -> {},{}</title></line>"#,
+                a.0,
+                a.1,
+                b.0,
+                b.1,
+                Orientation::of(segment).color(),
+                a.0,
+                a.1,
+                b.0,
+                b.1,
+            )?;
+        }
+        writeln!(svg, "</g>")?;
+
+        writeln!(svg, r##"<g fill="#e15759">"##)?;
+        for (x, y, &count) in Self::rasterize(segments, true).indices() {
+            if count >= 2 {
+                writeln!(
+                    svg,
+                    r#"<circle cx="{}.5" cy="{}.5" r="{}"><title>{},{}: {} lines</title></circle>"#,
+                    x,
+                    y,
+                    stroke * 1.5,
+                    x,
+                    y,
+                    count,
+                )?;
+            }
+        }
+        writeln!(svg, "</g>")?;
+
+        writeln!(svg, "</svg>")?;
+        Ok(svg)
     }
 
-    let answer = grid.iter().filter(|&&cell| cell >= 2).count();
-    println!("{}", answer);
+    /// Returns the size of the part of the grid covered by the segments
+    fn extent(segments: &[Segment]) -> (usize, usize) {
+        segments
+            .iter()
+            .flat_map(|&(a, b)| [a, b])
+            .fold((0, 0), |(width, height), (x, y)| (width.max(x + 1), height.max(y + 1)))
+    }
+}
 
-    Ok(())
+impl Solution for Day5 {
+    type Input = Vec<Segment>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+            .map(|line| {
+                let (a, b) = line.split_once(line.text, " -> ", "x1,y1 -> x2,y2")?;
+
+                let point = |span| {
+                    let (x, y): (usize, usize) = line.pair(span, "a coordinate")?;
+
+                    if x >= SIZE || y >= SIZE {
+                        return Err(line.error(span, format!("max size ({}) exceeded", SIZE)));
+                    }
+
+                    Ok((x, y))
+                };
+
+                let (a, b) = (point(a)?, point(b)?);
+
+                if a.0 != b.0 && a.1 != b.1 && a.0.abs_diff(b.0) != a.1.abs_diff(b.1) {
+                    return Err(line.error(line.text, "expected a horizontal, vertical or 45° diagonal segment"));
+                }
+
+                Ok((a, b))
+            })
+            .collect()
+    }
+
+    fn part_one(segments: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        Ok(Self::overlaps(segments, false))
+    }
+
+    fn part_two(segments: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        Ok(Self::overlaps(segments, true))
+    }
+
+    const REPORTS: &'static [&'static str] = &["svg"];
+
+    fn report(segments: &Self::Input, name: &str, _params: &Params) -> Result<String, Box<dyn Error>> {
+        match name {
+            "svg" => Ok(Self::svg(segments)?),
+            _ => Err(format!("no report named `{}`", name).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn svg_report() {
+        let example = "\
+0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+        let segments = Day5::parse(example).unwrap();
+        let svg = Day5::report(&segments, "svg", &Params::new()).unwrap();
+
+        assert_eq!(svg.matches("<line ").count(), 10);
+
+        let colored = |orientation: Orientation| svg.matches(&format!(r#"stroke="{}""#, orientation.color())).count();
+        assert_eq!(colored(Orientation::Horizontal), 4);
+        assert_eq!(colored(Orientation::Vertical), 2);
+        assert_eq!(colored(Orientation::Diagonal), 4);
+
+        assert_eq!(svg.matches("<circle ").count(), 12);
+    }
 }
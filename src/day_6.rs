@@ -1,40 +1,598 @@
-use std::error::Error;
+use std::{error::Error, fmt::{self, Write}, str::FromStr};
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    const DAYS: usize = 256;
-    const RESET_TIMEOUT: usize = 6;
-    const NEWBORN_TIMEOUT: usize = 8;
+use crate::{
+    bigint::BigUint,
+    json::Json,
+    matrix::{Matrix, Modular, Semiring},
+    parse::{self, ParseError},
+    solution::{Answer, Params, Solution},
+    viz::{self, Frame},
+};
 
-    debug_assert!(RESET_TIMEOUT <= NEWBORN_TIMEOUT);
+/// Timer value of a fish of the puzzle's species right after it has given birth
+pub const RESET_TIMEOUT: usize = 6;
+/// Timer value of a newborn fish of the puzzle's species
+pub const NEWBORN_TIMEOUT: usize = 8;
+/// Largest timer value of any species, which keeps the transition matrices small
+pub const MAX_TIMEOUT: usize = 100;
 
-    let mut fish: [usize; NEWBORN_TIMEOUT + 1] = [0; NEWBORN_TIMEOUT + 1];
+/// Days simulated in part 1
+pub const PART_ONE_DAYS: u64 = 80;
+/// Days simulated in part 2, and by default in the reports
+pub const PART_TWO_DAYS: u64 = 256;
 
-    for initial_fish in input
-        .split(',')
-        .map(str::trim)
+/// Most days that can be simulated with exact counts, which for the puzzle's species grow
+/// by about 4% of a digit a day
+pub const MAX_EXACT_DAYS: u64 = 100_000;
+/// Most days shown one by one when visualising or debugging
+const MAX_TRACED_DAYS: u64 = 1000;
+
+/// The timers of a species of lanternfish
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Species {
+    /// Timer value of a fish right after it has given birth
+    pub reset: usize,
+    /// Timer value of a newborn fish
+    pub newborn: usize,
+}
+
+impl Species {
+    /// # Panics
+    /// Panics if the reset timer exceeds the newborn timer, or the newborn timer exceeds
+    /// [`MAX_TIMEOUT`].
+    pub fn new(reset: usize, newborn: usize) -> Self {
+        assert!(reset <= newborn && newborn <= MAX_TIMEOUT, "invalid timers {}/{}", reset, newborn);
+        Self { reset, newborn }
+    }
+
+    /// Number of distinct timer values of the species
+    fn timers(self) -> usize {
+        self.newborn + 1
+    }
+}
+
+/// The puzzle's species
+impl Default for Species {
+    fn default() -> Self {
+        Self::new(RESET_TIMEOUT, NEWBORN_TIMEOUT)
+    }
+}
+
+/// Parses `reset/newborn` timers, e.g. `6/8`
+impl FromStr for Species {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (reset, newborn) = s
+            .split_once('/')
+            .ok_or_else(|| format!("invalid species `{}`, expected `reset/newborn` timers", s))?;
+
+        let timer = |t: &str| {
+            t.trim()
+                .parse::<usize>()
+                .map_err(|e| format!("invalid timer `{}` of species `{}`: {}", t, s, e))
+        };
+        let (reset, newborn) = (timer(reset)?, timer(newborn)?);
+
+        if newborn > MAX_TIMEOUT {
+            return Err(format!("newborn timer of species `{}` exceeds {}", s, MAX_TIMEOUT));
+        }
+
+        if reset > newborn {
+            return Err(format!("reset timer of species `{}` exceeds its newborn timer", s));
+        }
+
+        Ok(Self::new(reset, newborn))
+    }
+}
+
+impl fmt::Display for Species {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.reset, self.newborn)
+    }
+}
+
+/// The initial number of fish with each timer value, along with the simulation settings
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct School {
+    /// Number of fish with each timer value, up to the largest one present
+    pub fish: Vec<u64>,
+    /// Species which are simulated side by side, each starting with the same fish
+    pub species: Vec<Species>,
+    /// Days to simulate in both parts instead of the puzzle's
+    pub days: Option<u64>,
+    /// Modulus to count the fish with, or `None` to count them exactly
+    pub modulus: Option<u64>,
+}
+
+impl School {
+    /// Returns the initial state of the simulation: the number of fish with each timer value,
+    /// for each species in turn
+    fn initial_state(&self) -> Result<Vec<u64>, String> {
+        let mut state = vec![];
+
+        for &species in &self.species {
+            if self.fish.len() > species.timers() {
+                return Err(format!(
+                    "a fish has timer {}, which is too high for species {}",
+                    self.fish.len() - 1,
+                    species
+                ));
+            }
+
+            state.extend(&self.fish);
+            state.resize(state.len() + species.timers() - self.fish.len(), 0);
+        }
+
+        Ok(state)
+    }
+}
+
+/// Summary statistics of the growth of a population
+#[derive(Clone, PartialEq, Debug)]
+pub struct Growth {
+    pub days: u64,
+    pub initial: BigUint,
+    pub last: BigUint,
+    /// Average daily growth rate over the last cycle of the longest newborn timer, which evens
+    /// out the waves of births, or `None` if no days were simulated
+    pub recent: Option<f64>,
+    /// Average daily growth rate over all days, or `None` if no days were simulated
+    pub overall: Option<f64>,
+    /// Days it takes the population to double at the recent growth rate, or `None` if it
+    /// doesn't grow
+    pub doubling: Option<f64>,
+}
+
+impl Growth {
+    /// Computes the statistics from the base 2 logarithm of the total number of fish on each day
+    fn new(species: &[Species], initial: BigUint, last: BigUint, log_totals: &[f64]) -> Self {
+        let days = log_totals.len() - 1;
+        let cycle = species.iter().map(|species| species.timers()).max().unwrap_or(1);
+
+        // Average of the base 2 logarithm of the daily growth factor from the given day to the last
+        let slope = |from: usize| match from < days {
+            true => Some((log_totals[days] - log_totals[from]) / (days - from) as f64),
+            false => None,
+        };
+
+        let recent = slope(days.saturating_sub(cycle));
+
+        Self {
+            days: days as u64,
+            initial,
+            last,
+            recent: recent.map(|slope| slope.exp2() - 1.0),
+            overall: slope(0).map(|slope| slope.exp2() - 1.0),
+            doubling: recent.filter(|&slope| slope > 0.0).map(|slope| 1.0 / slope),
+        }
+    }
+
+    fn json(&self) -> Json {
+        Json::object()
+            .with("days", self.days)
+            .with("initial", Json::Number(self.initial.to_string()))
+            .with("last", Json::Number(self.last.to_string()))
+            .with("recent_growth", self.recent)
+            .with("overall_growth", self.overall)
+            .with("doubling_time", self.doubling)
+    }
+}
+
+impl fmt::Display for Growth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rate = |rate: Option<f64>| match rate {
+            Some(rate) => format!("{:.2}%", rate * 100.0),
+            None => "-".to_owned(),
+        };
+
+        writeln!(f, "days           {}", self.days)?;
+        writeln!(f, "initial fish   {}", self.initial)?;
+        writeln!(f, "final fish     {}", self.last)?;
+        writeln!(f, "daily growth   {} recently, {} overall", rate(self.recent), rate(self.overall))?;
+
+        match self.doubling {
+            Some(days) => writeln!(f, "doubling time  {:.2} days", days),
+            None => writeln!(f, "doubling time  -"),
+        }
+    }
+}
+
+pub struct Day6;
+
+impl Day6 {
+    /// Returns the matrix advancing the numbers of fish with each timer value of each species
+    /// by one day, with numbers of type `T` created by `num`. Since species don't interact,
+    /// the matrix is made of a block for each species along the diagonal.
+    fn transition<T: Semiring>(species: &[Species], num: impl Fn(u64) -> T) -> Matrix<T> {
+        // Species and timer value of each entry of the state
+        let entries: Vec<(usize, Species, usize)> = species
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &species)| (0..species.timers()).map(move |timer| (i, species, timer)))
+            .collect();
+
+        Matrix::from_fn(entries.len(), |row, col| {
+            let (block, species, timer) = entries[row];
+            let (prev_block, _, prev) = entries[col];
+
+            let becomes = match prev {
+                _ if block != prev_block => 0,
+                0 => (timer == species.reset) as u64 + (timer == species.newborn) as u64,
+                _ => (timer + 1 == prev) as u64,
+            };
+
+            num(becomes)
+        })
+    }
+
+    /// Returns the state of the simulation after the given number of days, which is the number
+    /// of fish with each timer value for each species in turn.
+    ///
+    /// The day's transition matrix is raised to the power of `days` by repeated squaring, so
+    /// this takes `O(log days)` matrix multiplications. Up to [`MAX_TRACED_DAYS`] days are also
+    /// stepped through one by one for visualisation and debugging if either is enabled.
+    pub fn population<T>(school: &School, days: u64, num: impl Fn(u64) -> T) -> Result<Vec<T>, String>
+    where
+        T: Semiring + fmt::Display,
     {
-        let initial_fish: usize = initial_fish.parse()?;
-        fish[initial_fish] += 1;
+        let initial: Vec<T> = school.initial_state()?.into_iter().map(&num).collect();
+        let step = Self::transition(&school.species, &num);
+
+        if viz::enabled() || log::log_enabled!(log::Level::Debug) {
+            let mut state = initial.clone();
+
+            log::debug!("initial state: {}", Self::state(&school.species, &state));
+            viz::emit(|| Self::frame(&school.species, &state, 0));
+
+            for day in 1..=days.min(MAX_TRACED_DAYS) {
+                state = step.apply(&state);
+
+                log::debug!("after {:>2} days: {}", day, Self::state(&school.species, &state));
+                viz::emit(|| Self::frame(&school.species, &state, day));
+            }
+        }
+
+        let identity = Matrix::from_fn(step.size(), |row, col| num((row == col) as u64));
+        Ok(step.pow(days, identity).apply(&initial))
+    }
+
+    /// Simulates the given number of days and returns the total number of fish, exactly
+    /// or modulo the school's modulus
+    fn simulate(school: &School, days: u64) -> Result<Answer, Box<dyn Error>> {
+        let days = school.days.unwrap_or(days);
+
+        match school.modulus {
+            Some(modulus) => {
+                let fish = Self::population(school, days, |n| Modular::new(n, modulus))?;
+                let total = Self::total(&fish);
+                Ok(Answer::new(total).with("days", days).with("modulus", modulus))
+            }
+            None if days > MAX_EXACT_DAYS => Err(format!(
+                "counting fish exactly for more than {} days takes too long, set a modulus",
+                MAX_EXACT_DAYS
+            )
+            .into()),
+            None => {
+                let fish = Self::population(school, days, BigUint::from)?;
+                Ok(Answer::new(Self::total(&fish)).with("days", days))
+            }
+        }
+    }
+
+    /// Steps through the simulation one day at a time with exact counts, calling `f` with
+    /// the state on each day from 0 to `days`, and returns statistics of the growth
+    pub fn each_day(
+        school: &School,
+        days: u64,
+        mut f: impl FnMut(u64, &[BigUint]) -> Result<(), Box<dyn Error>>,
+    ) -> Result<Growth, Box<dyn Error>> {
+        if school.modulus.is_some() {
+            return Err("a time series needs exact counts, without a modulus".into());
+        }
+
+        if days > MAX_EXACT_DAYS {
+            return Err(format!("a time series can't be longer than {} days", MAX_EXACT_DAYS).into());
+        }
+
+        let step = Self::transition(&school.species, BigUint::from);
+        let mut state: Vec<BigUint> = school.initial_state()?.into_iter().map(BigUint::from).collect();
+
+        let initial = Self::total(&state);
+        let mut log_totals = vec![initial.log2()];
+        f(0, &state)?;
+
+        for day in 1..=days {
+            state = step.apply(&state);
+            log_totals.push(Self::total(&state).log2());
+            f(day, &state)?;
+        }
+
+        Ok(Growth::new(&school.species, initial, Self::total(&state), &log_totals))
+    }
+
+    /// Formats the total and the number of fish with each timer value on each day as CSV
+    fn series_csv(school: &School, days: u64) -> Result<String, Box<dyn Error>> {
+        let mut csv = String::from("day,total");
+
+        for &species in &school.species {
+            for timer in 0..species.timers() {
+                match school.species.len() {
+                    1 => write!(csv, ",t{}", timer)?,
+                    _ => write!(csv, ",{}:t{}", species, timer)?,
+                }
+            }
+        }
+        csv.push('\n');
+
+        Self::each_day(school, days, |day, state| {
+            write!(csv, "{},{}", day, Self::total(state))?;
+            for count in state {
+                write!(csv, ",{}", count)?;
+            }
+            csv.push('\n');
+            Ok(())
+        })?;
+
+        Ok(csv)
+    }
+
+    /// Formats the total and the number of fish with each timer value of each species on each
+    /// day, along with the growth statistics, as JSON
+    fn series_json(school: &School, days: u64) -> Result<String, Box<dyn Error>> {
+        let number = |count: &BigUint| Json::Number(count.to_string());
+        let mut series = vec![];
+
+        let growth = Self::each_day(school, days, |day, state| {
+            let fish: Vec<Json> = Self::by_species(&school.species, state)
+                .map(|(_, fish)| Json::Array(fish.iter().map(number).collect()))
+                .collect();
+
+            series.push(
+                Json::object()
+                    .with("day", day)
+                    .with("total", number(&Self::total(state)))
+                    .with("fish", fish),
+            );
+            Ok(())
+        })?;
+
+        let species: Vec<String> = school.species.iter().map(Species::to_string).collect();
+        let json = Json::object()
+            .with("species", species)
+            .with("series", series)
+            .with("growth", growth.json());
+
+        Ok(format!("{}\n", json))
+    }
+
+    /// Splits the state of the simulation into the numbers of fish of each species
+    fn by_species<'a, T>(species: &'a [Species], state: &'a [T]) -> impl Iterator<Item = (Species, &'a [T])> {
+        species.iter().scan(state, |rest, &species| {
+            let (fish, tail) = rest.split_at(species.timers());
+            *rest = tail;
+            Some((species, fish))
+        })
+    }
+
+    fn total<T: Semiring>(fish: &[T]) -> T {
+        fish[1..].iter().fold(fish[0].clone(), |acc, count| acc.add(count))
+    }
+
+    /// Formats the numbers of fish with each timer value, prefixed by the species if there are
+    /// several
+    fn state<T: fmt::Display>(species: &[Species], state: &[T]) -> String {
+        let groups: Vec<String> = Self::by_species(species, state)
+            .map(|(kind, fish)| {
+                let counts: Vec<String> = fish.iter().map(|count| count.to_string()).collect();
+
+                match species.len() {
+                    1 => format!("[{}]", counts.join(", ")),
+                    _ => format!("{} [{}]", kind, counts.join(", ")),
+                }
+            })
+            .collect();
+
+        groups.join(" ")
     }
 
-    #[cfg(feature = "logging")]
-    println!("Initial state: {:?}", fish);
+    /// Draws the number of fish with each timer value as a bar chart, for each species in turn
+    fn frame<T: Semiring + fmt::Display>(species: &[Species], state: &[T], day: u64) -> Frame {
+        const WIDTH: usize = 60;
+
+        let max = state.iter().map(T::to_f64).fold(1.0, f64::max);
+        let mut chart = String::new();
 
-    for day in 0..DAYS {
-        let newborn = fish[0];
+        for (kind, fish) in Self::by_species(species, state) {
+            if species.len() > 1 {
+                chart += &format!("species {}, {} fish\n", kind, Self::total(fish));
+            }
 
-        for timeout in 1..=NEWBORN_TIMEOUT {
-            fish[timeout - 1] = fish[timeout];
+            for (timer, count) in fish.iter().enumerate() {
+                let bar = "#".repeat((count.to_f64() / max * WIDTH as f64) as usize);
+                chart += &format!("{:>3} | {:<width$} {}\n", timer, bar, count, width = WIDTH);
+            }
         }
 
-        fish[RESET_TIMEOUT] += newborn;
-        fish[NEWBORN_TIMEOUT] = newborn;
+        Frame::text(format!("day {}, {} fish", day, Self::total(state)), chart)
+    }
+}
+
+impl Solution for Day6 {
+    type Input = School;
+    type Answer1 = Answer;
+    type Answer2 = Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut fish = vec![];
+        let line = parse::single_line(input)?;
+
+        for span in line.text.split(',') {
+            let timer: usize = line.value(span, "a timer")?;
 
-        #[cfg(feature = "logging")]
-        println!("After {:>2} days: {:?}", day + 1, fish);
+            if timer > MAX_TIMEOUT {
+                return Err(line.error(span, format!("expected a timer from 0 to {}", MAX_TIMEOUT)));
+            }
+
+            if fish.len() <= timer {
+                fish.resize(timer + 1, 0);
+            }
+            fish[timer] += 1;
+        }
+
+        Ok(School {
+            fish,
+            species: vec![Species::default()],
+            days: None,
+            modulus: None,
+        })
+    }
+
+    fn part_one(school: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        Self::simulate(school, PART_ONE_DAYS)
+    }
+
+    fn part_two(school: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        Self::simulate(school, PART_TWO_DAYS)
     }
 
-    println!("{}", fish.iter().sum::<usize>());
+    const PARAMS: &'static [&'static str] = &["days", "modulus", "reset", "newborn", "species"];
+
+    fn configure(school: &mut Self::Input, params: &Params) -> Result<(), Box<dyn Error>> {
+        school.days = params.get("days")?;
+        school.modulus = params.get("modulus")?;
+
+        if school.modulus == Some(0) {
+            return Err("modulus must be positive".into());
+        }
+
+        let reset = params.get("reset")?;
+        let newborn = params.get("newborn")?;
 
-    Ok(())
+        school.species = match params.get_list("species", ',')? {
+            Some(_) if reset.is_some() || newborn.is_some() => {
+                return Err("`species` can't be combined with `reset` or `newborn`".into())
+            }
+            Some(species) => species,
+            None => {
+                let reset = reset.unwrap_or(RESET_TIMEOUT);
+                let newborn = newborn.unwrap_or(NEWBORN_TIMEOUT);
+                vec![format!("{}/{}", reset, newborn).parse()?]
+            }
+        };
+
+        Ok(())
+    }
+
+    const REPORTS: &'static [&'static str] = &["series", "series-json", "growth"];
+
+    fn report(school: &Self::Input, name: &str, _params: &Params) -> Result<String, Box<dyn Error>> {
+        let days = school.days.unwrap_or(PART_TWO_DAYS);
+
+        match name {
+            "series" => Self::series_csv(school, days),
+            "series-json" => Self::series_json(school, days),
+            "growth" => Ok(Self::each_day(school, days, |_, _| Ok(()))?.to_string()),
+            _ => Err(format!("no report named `{}`", name).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Simulates one day at a time
+    fn step_by_step(fish: &[u64], species: Species, days: u64) -> u128 {
+        let mut fish: Vec<u128> = fish.iter().map(|&count| count as u128).collect();
+        fish.resize(species.timers(), 0);
+
+        for _ in 0..days {
+            fish.rotate_left(1);
+            fish[species.reset] += fish[species.newborn];
+        }
+
+        fish.iter().sum()
+    }
+
+    #[test]
+    fn matrix_matches_step_by_step() {
+        let mut school = Day6::parse("3,4,3,1,2").unwrap();
+        let modulus = 1_000_000_007;
+
+        for species in [Species::default(), Species::new(2, 4), Species::new(4, 4), Species::new(0, 5)] {
+            school.species = vec![species];
+
+            for days in [0, 1, 18, 80, 256] {
+                let expected = step_by_step(&school.fish, species, days);
+
+                let exact = Day6::population(&school, days, BigUint::from).unwrap();
+                assert_eq!(Day6::total(&exact).to_string(), expected.to_string());
+
+                let modular = Day6::population(&school, days, |n| Modular::new(n, modulus)).unwrap();
+                assert_eq!(Day6::total(&modular).value() as u128, expected % modulus as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn settings() {
+        let mut school = Day6::parse("3,4,3,1,2").unwrap();
+        assert_eq!(Day6::part_two(&school).unwrap().value, "26984457539");
+
+        let mut params = Params::new();
+        params.set("days", "1000000000000");
+        params.set("modulus", "1000");
+        Day6::configure(&mut school, &params).unwrap();
+        assert!(Day6::part_one(&school).unwrap().value.parse::<u64>().unwrap() < 1000);
+
+        school.modulus = None;
+        assert!(Day6::part_one(&school).is_err());
+
+        params.set("modulus", "0");
+        assert!(Day6::configure(&mut school, &params).is_err());
+
+        let mut params = Params::new();
+        params.set("days", "80");
+        params.set("species", "6/8, 2/4");
+        Day6::configure(&mut school, &params).unwrap();
+        let both = step_by_step(&school.fish, Species::new(6, 8), 80) + step_by_step(&school.fish, Species::new(2, 4), 80);
+        assert_eq!(Day6::part_one(&school).unwrap().value, both.to_string());
+
+        // A fish with timer 4 can't be of a species whose newborns start at 3
+        params.set("species", "6/8,1/3");
+        Day6::configure(&mut school, &params).unwrap();
+        assert!(Day6::part_one(&school).is_err());
+
+        params.set("reset", "5");
+        assert!(Day6::configure(&mut school, &params).is_err());
+
+        for species in ["8/6", "6", "1/101", ""] {
+            params.set("species", species);
+            assert!(Day6::configure(&mut school, &params).is_err(), "{}", species);
+        }
+    }
+
+    #[test]
+    fn time_series() {
+        let school = Day6::parse("3,4,3,1,2").unwrap();
+
+        let mut totals = vec![];
+        let growth = Day6::each_day(&school, 18, |_, state| {
+            totals.push(Day6::total(state).to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(totals.len(), 19);
+        assert_eq!((&totals[0][..], &totals[18][..]), ("5", "26"));
+        assert_eq!((growth.initial.to_string(), growth.last.to_string()), ("5".to_owned(), "26".to_owned()));
+
+        let overall = growth.overall.unwrap();
+        assert!((5.0 * (1.0 + overall).powi(18) - 26.0).abs() < 1e-9);
+
+        let csv = Day6::report(&school, "series", &Params::new()).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("0,5,0,1,1,2,1,0,0,0,0"));
+        assert_eq!(csv.lines().count(), 2 + PART_TWO_DAYS as usize);
+    }
 }
@@ -1,24 +1,331 @@
-use std::error::Error;
+use std::{collections::BTreeMap, error::Error, fmt::Write};
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    let mut nums: Vec<u64> = input
-        .trim()
-        .split(',')
-        .map(str::parse)
-        .collect::<Result<_, _>>()?;
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Params, Solution},
+    table::Table,
+};
 
-    #[cfg(feature = "part_1")]
-    {
+/// Fuel cost of moving a crab by some distance.
+///
+/// Costs must grow with distance at a non-decreasing rate (be convex), so that the total cost
+/// of aligning crabs is convex in the target position, which [`Day7::optimise`] relies on.
+/// Any such function from distance to cost is a cost model.
+pub trait CostModel {
+    fn cost(&self, distance: u64) -> u64;
+}
+
+impl<F> CostModel for F
+where
+    F: Fn(u64) -> u64,
+{
+    fn cost(&self, distance: u64) -> u64 {
+        self(distance)
+    }
+}
+
+/// Every step costs 1 fuel, as in part 1
+pub struct Linear;
+
+impl CostModel for Linear {
+    fn cost(&self, distance: u64) -> u64 {
+        distance
+    }
+}
+
+/// Every step costs 1 more fuel than the previous one, as in part 2
+pub struct Triangular;
+
+impl CostModel for Triangular {
+    fn cost(&self, distance: u64) -> u64 {
+        distance * (distance + 1) / 2
+    }
+}
+
+/// Cost is the square of the distance
+pub struct Quadratic;
+
+impl CostModel for Quadratic {
+    fn cost(&self, distance: u64) -> u64 {
+        distance * distance
+    }
+}
+
+/// Looks up a built-in cost model by name
+pub fn cost_model(name: &str) -> Result<&'static dyn CostModel, String> {
+    match name {
+        "linear" => Ok(&Linear),
+        "triangular" => Ok(&Triangular),
+        "quadratic" => Ok(&Quadratic),
+        _ => Err(format!("unknown cost model `{}`, expected linear, triangular or quadratic", name)),
+    }
+}
+
+/// Height of the plot of the cost curve in lines
+const PLOT_HEIGHT: usize = 20;
+/// Maximum width of the plot of the cost curve in characters
+const PLOT_WIDTH: usize = 72;
+
+/// Positions of the crabs, along with the cost model of part 2 and the reports
+pub struct Crabs {
+    pub positions: Vec<u64>,
+    pub model: &'static dyn CostModel,
+}
+
+pub struct Day7;
+
+impl Day7 {
+    /// Returns the total fuel cost of moving all crabs to the given position
+    pub fn total_cost<C: CostModel + ?Sized>(crabs: &[u64], model: &C, position: u64) -> u64 {
+        crabs.iter().map(|&crab| model.cost(crab.abs_diff(position))).sum()
+    }
+
+    /// Finds the lowest position minimising the total fuel cost, returning it along with the cost,
+    /// or `None` if there are no crabs.
+    ///
+    /// Since the total cost is convex in the position, the minimum is where its slope stops being
+    /// negative, which is found by bisecting the range of crab positions in `O(n log r)` steps
+    /// for `n` crabs spread over `r` positions.
+    pub fn optimise<C: CostModel + ?Sized>(crabs: &[u64], model: &C) -> Option<(u64, u64)> {
+        let mut low = *crabs.iter().min()?;
+        let mut high = *crabs.iter().max()?;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+
+            if Self::total_cost(crabs, model, mid) <= Self::total_cost(crabs, model, mid + 1) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        Some((low, Self::total_cost(crabs, model, low)))
+    }
+
+    /// Returns the total fuel cost of every position from the leftmost to the rightmost crab,
+    /// computed by brute force
+    pub fn cost_curve<C: CostModel + ?Sized>(crabs: &[u64], model: &C) -> Vec<(u64, u64)> {
+        let (Some(&min), Some(&max)) = (crabs.iter().min(), crabs.iter().max()) else {
+            return vec![];
+        };
+
+        (min..=max)
+            .map(|pos| (pos, Self::total_cost(crabs, model, pos)))
+            .collect()
+    }
+
+    /// Formats the cost curve as CSV
+    fn curve_csv(curve: &[(u64, u64)]) -> Result<String, std::fmt::Error> {
+        let mut csv = String::from("position,cost\n");
+        for (pos, cost) in curve {
+            writeln!(csv, "{},{}", pos, cost)?;
+        }
+        Ok(csv)
+    }
+
+    /// Plots the cost curve as columns of `#`, marking the lowest cost with `o`.
+    /// Wide curves are scaled down, with each column showing the lowest cost among its positions.
+    fn curve_plot(curve: &[(u64, u64)]) -> Result<String, std::fmt::Error> {
+        let (Some(&(first, _)), Some(&(last, _))) = (curve.first(), curve.last()) else {
+            return Ok(String::new());
+        };
+
+        let columns: Vec<u64> = curve
+            .chunks(curve.len().div_ceil(PLOT_WIDTH))
+            .map(|chunk| chunk.iter().map(|&(_, cost)| cost).min().unwrap())
+            .collect();
+
+        let min = *columns.iter().min().unwrap();
+        let max = *columns.iter().max().unwrap();
+        let label = max.to_string().len();
+
+        // Number of filled rows of a column, at least 1 so that the minimum stays visible
+        let height = |cost: u64| match max > min {
+            true => 1 + ((cost - min) as u128 * (PLOT_HEIGHT - 1) as u128 / (max - min) as u128) as usize,
+            false => 1,
+        };
+
+        let mut plot = String::new();
+
+        for row in (1..=PLOT_HEIGHT).rev() {
+            let axis = match row {
+                PLOT_HEIGHT => max.to_string(),
+                1 => min.to_string(),
+                _ => String::new(),
+            };
+            write!(plot, "{:>label$} |", axis)?;
+
+            for &cost in &columns {
+                plot.push(match height(cost) {
+                    h if h < row => ' ',
+                    _ if cost == min && row == 1 => 'o',
+                    _ => '#',
+                });
+            }
+            plot.push('\n');
+        }
+
+        writeln!(plot, "{:>label$} +{}", "", "-".repeat(columns.len()))?;
+        // Labels the ends of the axis, keeping them apart when the plot is too narrow for both
+        let (first, last) = (first.to_string(), last.to_string());
+        let labels = match columns.len() {
+            _ if first == last => first,
+            width if width > first.len() + last.len() => format!("{:<pad$}{}", first, last, pad = width - last.len()),
+            _ => format!("{} {}", first, last),
+        };
+        writeln!(plot, "{:>label$}  {}", "", labels)?;
+
+        Ok(plot)
+    }
+
+    /// Describes the positions tied for the lowest cost, checking them against the optimiser,
+    /// and the fuel spent by the crabs at each position to get to the optimiser's choice
+    fn optimum<C: CostModel + ?Sized>(crabs: &[u64], model: &C) -> Result<String, Box<dyn Error>> {
+        let curve = Self::cost_curve(crabs, model);
+        let (pos, cost) = Self::optimise(crabs, model).ok_or("no crabs")?;
+
+        let lowest = curve.iter().map(|&(_, cost)| cost).min().unwrap();
+        let tied: Vec<String> = curve
+            .iter()
+            .filter(|&&(_, cost)| cost == lowest)
+            .map(|(pos, _)| pos.to_string())
+            .collect();
+
+        let mut report = String::new();
+        writeln!(report, "optimiser: position {}, cost {}", pos, cost)?;
+        writeln!(report, "brute force: cost {} at positions {}", lowest, tied.join(", "))?;
+        writeln!(report, "{}\n", if cost == lowest { "optimiser agrees" } else { "OPTIMISER DISAGREES" })?;
+
+        let mut counts = BTreeMap::new();
+        for &crab in crabs {
+            *counts.entry(crab).or_insert(0u64) += 1;
+        }
+
+        let mut table = Table::new(["Crab", "Count", "Distance", "Fuel", "Total"]);
+        for (crab, count) in counts {
+            let fuel = model.cost(crab.abs_diff(pos));
+            table.row([
+                crab.to_string(),
+                count.to_string(),
+                crab.abs_diff(pos).to_string(),
+                fuel.to_string(),
+                (fuel * count).to_string(),
+            ]);
+        }
+
+        write!(report, "{}", table)?;
+        Ok(report)
+    }
+}
+
+impl Solution for Day7 {
+    type Input = Crabs;
+    type Answer1 = Answer;
+    type Answer2 = Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Crabs {
+            positions: parse::single_line(input)?.list(",", "a position")?,
+            model: &Triangular,
+        })
+    }
+
+    fn part_one(crabs: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        let mut nums = crabs.positions.clone();
         nums.sort();
 
         let med = nums[nums.len() / 2];
 
-        #[cfg(feature = "logging")]
-        println!("med = {}", med);
+        log::debug!("median = {}", med);
 
         let cost: u64 = nums.iter().map(|&n| n.abs_diff(med)).sum();
-        println!("{:?}", cost);
+        Ok(Answer::new(cost).with("med", med))
+    }
+
+    fn part_two(crabs: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        let (pos, cost) = Self::optimise(&crabs.positions, crabs.model).ok_or("no crabs")?;
+
+        log::debug!("optimal position = {}", pos);
+
+        Ok(Answer::new(cost).with("pos", pos))
+    }
+
+    const PARAMS: &'static [&'static str] = &["cost"];
+
+    fn configure(crabs: &mut Self::Input, params: &Params) -> Result<(), Box<dyn Error>> {
+        if let Some(name) = params.get::<String>("cost")? {
+            crabs.model = cost_model(&name)?;
+        }
+
+        Ok(())
+    }
+
+    const REPORTS: &'static [&'static str] = &["curve", "plot", "optimum"];
+
+    fn report(crabs: &Self::Input, name: &str, _params: &Params) -> Result<String, Box<dyn Error>> {
+        let (positions, model) = (&crabs.positions, crabs.model);
+
+        match name {
+            "curve" => Ok(Self::curve_csv(&Self::cost_curve(positions, model))?),
+            "plot" => Ok(Self::curve_plot(&Self::cost_curve(positions, model))?),
+            "optimum" => Self::optimum(positions, model),
+            _ => Err(format!("no report named `{}`", name).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn brute_force(crabs: &[u64], model: &dyn CostModel) -> (u64, u64) {
+        let max = *crabs.iter().max().unwrap();
+
+        (0..=max)
+            .map(|pos| (pos, Day7::total_cost(crabs, model, pos)))
+            .min_by_key(|&(pos, cost)| (cost, pos))
+            .unwrap()
     }
 
-    Ok(())
+    #[test]
+    fn optimise_matches_brute_force() {
+        let crabs = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let cubic = |d: u64| d * d * d;
+        let flat = |_: u64| 0;
+
+        let models: [&dyn CostModel; 5] = [&Linear, &Triangular, &Quadratic, &cubic, &flat];
+        for model in models {
+            assert_eq!(Day7::optimise(&crabs, model), Some(brute_force(&crabs, model)));
+        }
+
+        assert_eq!(Day7::optimise(&crabs, &Triangular), Some((5, 168)));
+        assert_eq!(Day7::optimise(&[], &Linear), None);
+    }
+
+    #[test]
+    fn plot_axis_labels() {
+        let axis = |crabs: &[u64]| {
+            let plot = Day7::curve_plot(&Day7::cost_curve(crabs, &Triangular)).unwrap();
+            plot.lines().last().unwrap().trim().to_owned()
+        };
+
+        assert_eq!(axis(&[100, 101]), "100 101");
+        assert_eq!(axis(&[5]), "5");
+        assert_eq!(axis(&[0, 20]), format!("0{}20", " ".repeat(18)));
+    }
+
+    #[test]
+    fn cost_setting() {
+        let mut crabs = Day7::parse("16,1,2,0,4,2,7,1,2,14").unwrap();
+        assert_eq!(Day7::part_two(&crabs).unwrap().value, "168");
+
+        let mut params = Params::new();
+        params.set("cost", "linear");
+        Day7::configure(&mut crabs, &params).unwrap();
+        assert_eq!(Day7::part_two(&crabs).unwrap().value, "37");
+
+        params.set("cost", "cubic");
+        assert!(Day7::configure(&mut crabs, &params).is_err());
+    }
 }
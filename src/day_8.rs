@@ -1,5 +1,6 @@
 use std::{
     collections::HashSet,
+    convert::Infallible,
     error::Error,
     fmt::{self, Write},
     hash::Hash,
@@ -7,8 +8,13 @@ use std::{
     str::FromStr,
 };
 
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
+};
+
 /// Patterns representing digits from 0 to 9
-const DIGITS: [Pattern; 10] = [
+pub const DIGITS: [Pattern; 10] = [
     //                        GFEDCBA
     Pattern::from_bit_set(0b0_1110111),
     Pattern::from_bit_set(0b0_0100100),
@@ -31,13 +37,13 @@ const DIGITS: [Pattern; 10] = [
 ///  - a pattern representing the "intersection" of 5-segment digits: `ADG??`
 ///
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Pattern {
+pub struct Pattern {
     set: u8,
     unbound: u8,
 }
 
 impl FromStr for Pattern {
-    type Err = !;
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self {
@@ -55,7 +61,7 @@ impl FromStr for Pattern {
 
 impl fmt::Debug for Pattern {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.len() == 0 {
+        if self.is_empty() {
             return f.write_str("{}");
         }
 
@@ -120,25 +126,30 @@ impl ops::Mul<Pattern> for Pattern {
 
 impl Pattern {
     /// Constructs a pattern with bound elements given by the bit set and no unbound elements.
-    const fn from_bit_set(set: u8) -> Self {
+    pub const fn from_bit_set(set: u8) -> Self {
         Self { set, unbound: 0 }
     }
 
     /// Returns the total number of elements (both bound and unbound) in the set.
-    const fn len(self) -> usize {
+    pub const fn len(self) -> usize {
         self.set.count_ones() as usize + self.unbound as usize
     }
 
+    /// Returns `true` if the set contains no elements, bound or unbound.
+    pub const fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
     /// Returns the number of bound elements in the set.
-    const fn len_bound(self) -> usize {
+    pub const fn len_bound(self) -> usize {
         self.set.count_ones() as usize
     }
 
     /// Returns the bit index (0 is least significant) of the single bound element in this set,
     /// only if the set contains exactly one element which is bound. Returns `None` otherwise.
-    const fn singleton_index(self) -> Option<u8> {
+    pub const fn singleton_index(self) -> Option<u8> {
         if self.unbound == 0 && self.set != 0 && self.set.is_power_of_two() {
-            Some(self.set.log2() as u8)
+            Some(self.set.ilog2() as u8)
         } else {
             None
         }
@@ -149,14 +160,14 @@ impl Pattern {
     ///
     /// Always returns `false` if the right-hand-side set contains unbound elements, since they
     /// cannot be compared for equality.
-    const fn contains(self, rhs: Self) -> bool {
+    pub const fn contains(self, rhs: Self) -> bool {
         self.set & rhs.set == rhs.set && rhs.unbound == 0
     }
 }
 
 /// Resolves display segment mappings based on example patterns
 #[derive(Default)]
-struct Solver {
+pub struct Solver {
     /// Each entry `(a, b)` represents the assertion `a = b` (equality of sets, unrelated to [`Eq`]),
     /// e.g. `(adg??, bcdef)` means `{A,D,G,?,?} = {b,c,d,e,f}`, where the left-hand-side
     /// represents the "original" pattern and the right-hand-side represents the mapped pattern.
@@ -168,7 +179,7 @@ impl Solver {
     /// The pattern (of length `n`) represents the result of mapping a `n`-segment digit
     /// using the solved mapping. Behavior is unpredictable if two contradictory examples
     /// are given to this method.
-    fn learn(&mut self, example: Pattern) {
+    pub fn learn(&mut self, example: Pattern) {
         if let Some(pattern) = DIGITS
             .iter()
             .copied()
@@ -190,7 +201,7 @@ impl Solver {
     /// Iterates the solving algorithm the given number of times and returns the resulting mapping.
     /// The mapping is not guaranteed to be valid (`.is_valid()`), e.g. if not enough example
     /// patterns were given or not enough iterations have been executed.
-    fn solve(&mut self, iterations: usize) -> Mapping {
+    pub fn solve(&mut self, iterations: usize) -> Mapping {
         for _ in 0..iterations {
             let mut new_knowledge = HashSet::new();
 
@@ -231,7 +242,7 @@ impl Solver {
 /// Stores the solved segment mapping
 /// (`mapping[original_segment]` = singleton bit set representing the mapped segment)
 #[derive(Clone, Copy)]
-struct Mapping([u8; 7]);
+pub struct Mapping([u8; 7]);
 
 impl fmt::Debug for Mapping {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -247,12 +258,15 @@ impl fmt::Debug for Mapping {
 }
 
 impl Mapping {
-    fn is_valid(&self) -> bool {
+    /// Returns `true` if every original segment is mapped to exactly one segment, and no two
+    /// original segments are mapped to the same one
+    pub fn is_valid(&self) -> bool {
         self.0.iter().all(|&m| m != 0 && m.is_power_of_two())
+            && self.0.iter().fold(0, |all, &m| all | m).count_ones() == 7
     }
 
     /// Reverses the solved mapping on the given pattern, producing the original pattern
-    fn decode(&self, input: Pattern) -> Pattern {
+    pub fn decode(&self, input: Pattern) -> Pattern {
         let mut result = 0;
 
         let mut original = 1;
@@ -272,56 +286,108 @@ impl Mapping {
     }
 }
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    let mut sum = 0;
-
-    for line in input.lines() {
-        let (examples, inputs) = line.split_once(" | ").unwrap();
+pub struct Day8;
 
+impl Day8 {
+    /// Solves the segment mapping from the examples and decodes the inputs into digits.
+    /// Fails if the examples contradict each other, so that they don't show each digit once
+    /// under a valid mapping, or if an input isn't a digit under the mapping.
+    fn decode(examples: &[Pattern], inputs: &[Pattern]) -> Result<Vec<usize>, String> {
         // Train the solver on the examples
         let mut solver = Solver::default();
-        for example in examples
-            .split_ascii_whitespace()
-            .map(|e| e.parse().unwrap())
-        {
+        for &example in examples {
             solver.learn(example);
         }
 
         // Solve the mapping
         let mapping = solver.solve(3);
-        assert!(mapping.is_valid());
-
-        #[cfg(feature = "logging")]
-        println!("{:?}", mapping);
+        if !mapping.is_valid() {
+            return Err("the example patterns don't determine a segment mapping".into());
+        }
 
-        // Reverse the mapping on the inputs
-        let inputs = inputs.split_ascii_whitespace();
+        log::debug!("{:?}", mapping);
 
-        let mut result = 0;
-        for digit in inputs
-            .map(|i| i.parse().unwrap())
-            .map(|i| mapping.decode(i))
-            // Find digit indices matching the digit patterns
-            .map(|d| DIGITS.iter().position(|&p| p == d).unwrap())
-        {
-            #[cfg(feature = "part_1")]
-            if [1, 4, 7, 8].contains(&digit) {
-                result += 1;
+        // Reverse the mapping and find the digit index matching the digit pattern
+        let digit = |pattern: Pattern| {
+            DIGITS
+                .iter()
+                .position(|&p| p == mapping.decode(pattern))
+                .ok_or_else(|| format!("pattern `{:?}` is not a digit under the solved mapping", pattern))
+        };
+
+        let mut seen = [false; 10];
+        for &example in examples {
+            let digit = digit(example)?;
+            if std::mem::replace(&mut seen[digit], true) {
+                return Err(format!("the example patterns show digit {} more than once", digit));
             }
-
-            #[cfg(feature = "part_2")]
-            { result = result * 10 + digit; }
         }
 
-        #[cfg(feature = "logging")]
-        println!("result = {}\n", result);
+        inputs.iter().map(|&input| digit(input)).collect()
+    }
+}
+
+impl Solution for Day8 {
+    type Input = Vec<(Vec<Pattern>, Vec<Pattern>)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+            .map(|line| {
+                let (examples, inputs) = line.split_once(line.text, " | ", "<examples> | <inputs>")?;
+
+                let parse = |span: &str, count: usize| {
+                    if let Some(segment) = line.find_invalid(span, |ch| ch.is_whitespace() || ('a'..='g').contains(&ch)) {
+                        return Err(line.error(segment, "expected segments `a` to `g`"));
+                    }
+
+                    let patterns: Vec<Pattern> = line.fields(span, "a pattern")?;
 
-        sum += result;
+                    if patterns.len() != count {
+                        let message = format!("expected {} patterns, found {}", count, patterns.len());
+                        return Err(line.error(span, message));
+                    }
+
+                    Ok(patterns)
+                };
+
+                let (examples, inputs) = (parse(examples, 10)?, parse(inputs, 4)?);
+                Self::decode(&examples, &inputs).map_err(|message| line.error(line.text, message))?;
+
+                Ok((examples, inputs))
+            })
+            .collect()
     }
 
-    println!("sum = {}", sum);
+    fn part_one(displays: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        let mut count = 0;
+
+        for (examples, inputs) in displays {
+            count += Self::decode(examples, inputs)?
+                .into_iter()
+                .filter(|digit| [1, 4, 7, 8].contains(digit))
+                .count();
+        }
+
+        Ok(count)
+    }
+
+    fn part_two(displays: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        let mut sum = 0;
+
+        for (examples, inputs) in displays {
+            let result = Self::decode(examples, inputs)?
+                .into_iter()
+                .fold(0, |result, digit| result * 10 + digit);
 
-    Ok(())
+            log::debug!("result = {}", result);
+
+            sum += result;
+        }
+
+        Ok(sum)
+    }
 }
 
 #[cfg(test)]
@@ -346,4 +412,19 @@ mod test {
             assert_eq!(i, identity.decode(i));
         }
     }
+
+    #[test]
+    fn contradictory_patterns() {
+        let valid = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert!(Day8::parse(valid).is_ok());
+
+        for input in [
+            "ab ab ab ab ab ab ab ab ab ab | ab ab ab ab",
+            "aabbcc ab ab ab ab ab ab ab ab ab | ab ab ab ab",
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb ac",
+        ] {
+            let error = Day8::parse(&format!("{}\n{}", valid, input)).unwrap_err();
+            assert_eq!(error.line, 2, "{}", error);
+        }
+    }
 }
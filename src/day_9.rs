@@ -1,122 +1,67 @@
-use std::{collections::HashSet, error::Error};
-
-struct Grid<T> {
-    cells: Vec<Vec<T>>,
-    cols: usize,
-    rows: usize,
-}
-
-impl<T> Grid<T> {
-    fn parse<F>(input: &str, f: F) -> Self
-    where
-        F: Fn(char) -> T,
-    {
-        let cells: Vec<Vec<T>> = input
-            .lines()
-            .map(str::trim)
-            .filter(|line| !line.is_empty())
-            .map(|line| line.chars().map(&f).collect())
-            .collect();
-
-        let cols = cells.get(0).map(Vec::len).unwrap_or(0);
-        let rows = cells.len();
-
-        Self { cells, cols, rows }
+use std::error::Error;
+
+use crate::{
+    grid::Grid,
+    parse::ParseError,
+    solution::Solution,
+    viz::{self, Frame},
+};
+
+pub struct Day9;
+
+impl Day9 {
+    /// Returns the positions of all points lower than any of their neighbors
+    fn low_points(heightmap: &Grid<u8>) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        heightmap
+            .indices()
+            .filter(|&(x, y, h)| heightmap.neighbors4(x, y).all(|(_, _, h2)| h2 > h))
+            .map(|(x, y, &h)| (x, y, h))
     }
+}
 
-    fn indices(&self) -> impl Iterator<Item = (usize, usize, &T)> {
-        (0..self.cols).flat_map(move |x| (0..self.rows).map(move |y| (x, y, &self.cells[y][x])))
-    }
+impl Solution for Day9 {
+    type Input = Grid<u8>;
+    type Answer1 = u64;
+    type Answer2 = usize;
 
-    fn get(&self, col: isize, row: isize) -> Option<&T> {
-        if col < 0 || col >= self.cols as isize || row < 0 || row >= self.rows as isize {
-            None
-        } else {
-            Some(&self.cells[row as usize][col as usize])
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Grid::parse(input, "a height", |ch| ch.to_digit(10).map(|h| h as u8))
     }
 
-    fn flood_count<B>(&self, x: usize, y: usize, is_boundary: B) -> usize
-    where
-        B: Fn(&T) -> bool,
-    {
-        let mut to_flood = HashSet::new();
-        to_flood.insert((x, y));
-
-        let mut flooded = HashSet::new();
-        let mut next = HashSet::new();
-
-        let mut count = 0;
-
-        while !to_flood.is_empty() {
-            for (x, y) in to_flood.drain() {
-                if !is_boundary(&self.cells[y][x]) {
-                    count += 1;
+    fn part_one(heightmap: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        viz::emit(|| {
+            Frame::grid("low points", heightmap.map(|&h| h as u32))
+                .with_highlights(Self::low_points(heightmap).map(|(x, y, _)| (x, y)))
+        });
 
-                    for (dx, dy) in [(-1isize, 0isize), (1, 0), (0, -1), (0, 1)] {
-                        let (x, y) = (x as isize + dx, y as isize + dy);
-                        if self.get(x, y).is_some() {
-                            next.insert((x as usize, y as usize));
-                        }
-                    }
-
-                    flooded.insert((x, y));
-                }
-            }
-
-            to_flood.extend(next.drain().filter(|pos| !flooded.contains(pos)));
-        }
-
-        count
+        Ok(Self::low_points(heightmap)
+            .map(|(_, _, h)| h as u64 + 1)
+            .sum())
     }
-}
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    let heightmap = Grid::parse(input, |ch| ch.to_digit(10).unwrap() as u8);
+    fn part_two(heightmap: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        let mut basins: Vec<_> = Self::low_points(heightmap)
+            .map(|(x, y, _)| heightmap.flood(x, y, |&h| h >= 9))
+            .collect();
 
-    #[cfg(feature = "part_1")]
-    let mut total_risk = 0u64;
-    #[cfg(feature = "part_2")]
-    let mut basins = vec![];
+        basins.sort_by_key(|basin| basin.len());
 
-    for (x, y, &h) in heightmap.indices() {
-        let mut risk = h + 1;
+        if viz::enabled() {
+            let heights = heightmap.map(|&h| h as u32);
 
-        for (dx, dy) in [(-1isize, 0isize), (1, 0), (0, -1), (0, 1)] {
-            if let Some(&h2) = heightmap.get(x as isize + dx, y as isize + dy) {
-                if h2 <= h {
-                    risk = 0;
-                    break;
-                }
+            for (i, basin) in basins.iter().rev().enumerate() {
+                let caption = format!("basin {} of {}, size {}", i + 1, basins.len(), basin.len());
+                viz::emit(|| Frame::grid(caption, heights.clone()).with_highlights(basin.iter().copied()));
             }
-        }
-
-        #[cfg(feature = "part_1")]
-        {
-            total_risk += risk as u64;
-        }
 
-        #[cfg(feature = "part_2")]
-        if risk > 0 {
-            basins.push(heightmap.flood_count(x, y, |&h| h >= 9));
+            let largest = basins.iter().rev().take(3).flatten().copied();
+            viz::emit(|| Frame::grid("3 largest basins", heights).with_highlights(largest));
         }
-    }
 
-    #[cfg(feature = "part_1")]
-    println!("{}", total_risk);
+        let basins: Vec<_> = basins.iter().map(|basin| basin.len()).collect();
 
-    #[cfg(feature = "part_2")]
-    {
-        basins.sort();
-        let result = basins
-            .into_iter()
-            .rev()
-            .take(3)
-            .inspect(|b| print!("{} ", b))
-            .fold(1, |a, b| a * b);
+        log::debug!("basins = {:?}", &basins[basins.len().saturating_sub(3)..]);
 
-        println!("{}", result);
+        Ok(basins.into_iter().rev().take(3).product())
     }
-
-    Ok(())
 }
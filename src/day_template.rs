@@ -1,5 +1,23 @@
 use std::error::Error;
 
-pub(crate) fn main(input: &str) -> Result<(), Box<dyn Error>> {
-    Ok(())
+use crate::{parse::ParseError, solution::Solution};
+
+pub struct DayN;
+
+impl Solution for DayN {
+    type Input = ();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(_input: &str) -> Result<Self::Input, ParseError> {
+        Ok(())
+    }
+
+    fn part_one(_input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+        Ok(0)
+    }
+
+    fn part_two(_input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+        Ok(0)
+    }
 }
@@ -0,0 +1,175 @@
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::input::input_path;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable holding the session cookie, as used by `aoc.py`
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable overriding the base URL, e.g. to point at a mock server
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Config file, in the same `KEY=value` format as `aoc.py`'s `.env`, consulted for
+/// variables missing from the environment
+pub const CONFIG_FILE: &str = ".env";
+
+const USER_AGENT: &str = "aoc-2021 (https://github.com/michalwa/aoc-2021)";
+
+/// Looks up a variable in the environment, falling back to the config file in `root`
+pub fn config_var(root: &Path, name: &str) -> Option<String> {
+    if let Ok(value) = env::var(name) {
+        return Some(value);
+    }
+
+    let config = fs::read_to_string(root.join(CONFIG_FILE)).ok()?;
+
+    config.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().trim_matches('"').to_owned())
+    })
+}
+
+/// Downloads pages and inputs of Advent of Code puzzles
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session,
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    /// Configures a client from the environment or the config file in `root`,
+    /// unless the base URL is given explicitly
+    pub fn from_config(root: &Path, base_url: Option<&str>) -> Self {
+        let base_url = base_url
+            .map(str::to_owned)
+            .or_else(|| config_var(root, BASE_URL_VAR))
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_owned());
+
+        Self::new(&base_url, config_var(root, SESSION_VAR))
+    }
+
+    fn get(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!("{}{}", self.base_url, path);
+
+        let mut request = self.agent.get(&url);
+        if let Some(session) = &self.session {
+            request = request.set("Cookie", &format!("session={}", session));
+        }
+
+        match request.call() {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, _)) => Err(format!("GET {}: status {}", url, code).into()),
+            Err(e) => Err(format!("GET {}: {}", url, e).into()),
+        }
+    }
+
+    /// Downloads the puzzle input. Requires a session.
+    pub fn input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        if self.session.is_none() {
+            return Err(format!("no session token, set {} or add it to {}", SESSION_VAR, CONFIG_FILE).into());
+        }
+
+        self.get(&format!("/{}/day/{}/input", year, day))
+    }
+
+    /// Downloads the puzzle description page
+    pub fn puzzle_page(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        self.get(&format!("/{}/day/{}", year, day))
+    }
+}
+
+/// Downloads the input for the day into `inputs/` under `root`, unless it's already there.
+/// Returns the path of the input and whether it was downloaded.
+pub fn fetch_input(client: &Client, root: &Path, year: u32, day: u32) -> Result<(PathBuf, bool), Box<dyn Error>> {
+    let path = root.join(input_path(day, false));
+
+    if path.exists() {
+        return Ok((path, false));
+    }
+
+    let input = client.input(year, day)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, input)?;
+
+    Ok((path, true))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Serves a single request, responding with the given body
+    /// and returning the request line and headers
+    fn mock_server(body: &'static str) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let request: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect();
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body,
+            )
+            .unwrap();
+
+            request
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn fetch_and_cache_input() {
+        let root = env::temp_dir().join(format!("aoc-2021-fetch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let (url, server) = mock_server("1,2,3\n");
+        let client = Client::new(&url, Some("secret".into()));
+
+        let (path, downloaded) = fetch_input(&client, &root, 2021, 6).unwrap();
+        assert!(downloaded);
+        assert_eq!(path, root.join("inputs/day6.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1,2,3\n");
+
+        let request = server.join().unwrap();
+        assert_eq!(request[0], "GET /2021/day/6/input HTTP/1.1");
+        assert!(request.iter().any(|h| h.eq_ignore_ascii_case("cookie: session=secret")));
+
+        // The mock server is gone, so this only succeeds without a request
+        let (_, downloaded) = fetch_input(&client, &root, 2021, 6).unwrap();
+        assert!(!downloaded);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+//! A rectangular grid of cells, for days working on 2D maps
+
+use std::{
+    collections::HashSet,
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::parse::{self, ParseError};
+
+/// Offsets of the 4 orthogonal neighbors of a cell
+pub const NEIGHBORS_4: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Offsets of the 8 orthogonal and diagonal neighbors of a cell
+pub const NEIGHBORS_8: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// A rectangular grid of cells, stored row by row and indexed by `(col, row)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Constructs a grid filled with copies of `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Constructs a grid from its rows.
+    ///
+    /// # Panics
+    /// Panics if the rows differ in length.
+    pub fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: IntoIterator<Item = T>,
+    {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for row in rows {
+            let len = cells.len();
+            cells.extend(row);
+
+            let row_width = cells.len() - len;
+            assert_eq!(*width.get_or_insert(row_width), row_width, "rows of a grid must have equal lengths");
+            height += 1;
+        }
+
+        Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        }
+    }
+
+    /// Parses a grid from non-empty lines of the input, converting each character with `f`.
+    /// `what` describes the characters accepted by `f`.
+    pub fn parse<F>(input: &str, what: &str, f: F) -> Result<Self, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        Ok(Self::from_rows(parse::char_grid(input, what, f)?))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cell at the given position, or `None` if it's out of bounds
+    pub fn get(&self, col: usize, row: usize) -> Option<&T> {
+        (col < self.width && row < self.height).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, col: usize, row: usize) -> Option<&mut T> {
+        if col < self.width && row < self.height {
+            Some(&mut self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the position offset from the given one by `(dx, dy)`, or `None` if it's out of bounds
+    pub fn offset(&self, col: usize, row: usize, (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let col = col.checked_add_signed(dx).filter(|&col| col < self.width)?;
+        let row = row.checked_add_signed(dy).filter(|&row| row < self.height)?;
+        Some((col, row))
+    }
+
+    /// Returns the cell at the given signed position, or `None` if it's out of bounds
+    pub fn get_signed(&self, col: isize, row: isize) -> Option<&T> {
+        self.get(usize::try_from(col).ok()?, usize::try_from(row).ok()?)
+    }
+
+    /// Iterates over all cells, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Iterates over all cells along with their column and row, row by row
+    pub fn indices(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (i % self.width, i / self.width, cell))
+    }
+
+    /// Returns the cells of the given row.
+    ///
+    /// # Panics
+    /// Panics if the row is out of bounds.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.height, "row {} out of bounds", row);
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |row| self.row(row))
+    }
+
+    /// Iterates over the cells of the given column.
+    ///
+    /// # Panics
+    /// Panics if the column is out of bounds.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {} out of bounds", col);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.column(col))
+    }
+
+    /// Iterates over the in-bounds neighbors of the given position at the given offsets,
+    /// e.g. [`NEIGHBORS_4`] or [`NEIGHBORS_8`]
+    pub fn neighbors<'a>(
+        &'a self,
+        col: usize,
+        row: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize, &'a T)> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&offset| self.offset(col, row, offset))
+            .map(move |(col, row)| (col, row, &self[(col, row)]))
+    }
+
+    /// Iterates over the orthogonal neighbors of the given position
+    pub fn neighbors4(&self, col: usize, row: usize) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.neighbors(col, row, &NEIGHBORS_4)
+    }
+
+    /// Iterates over the orthogonal and diagonal neighbors of the given position
+    pub fn neighbors8(&self, col: usize, row: usize) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.neighbors(col, row, &NEIGHBORS_8)
+    }
+
+    /// Constructs a grid of the same shape by converting each cell with `f`
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Returns the grid flipped over its diagonal, so that rows become columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_rows(self.columns().map(|col| col.cloned().collect::<Vec<_>>()))
+    }
+
+    /// Returns the part of the grid of the given size, starting at the given position.
+    /// The part is truncated at the edges of the grid.
+    pub fn crop(&self, col: usize, row: usize, width: usize, height: usize) -> Self
+    where
+        T: Clone,
+    {
+        let cols = col.min(self.width)..(col + width).min(self.width);
+
+        Self::from_rows(
+            (row.min(self.height)..(row + height).min(self.height)).map(|row| self.row(row)[cols.clone()].to_vec()),
+        )
+    }
+
+    /// Counts the cells reachable from the given position through orthogonal neighbors
+    /// without crossing a boundary
+    pub fn flood_count<B>(&self, col: usize, row: usize, is_boundary: B) -> usize
+    where
+        B: Fn(&T) -> bool,
+    {
+        self.flood(col, row, is_boundary).len()
+    }
+
+    /// Returns the positions of the cells reachable from the given position through
+    /// orthogonal neighbors without crossing a boundary
+    pub fn flood<B>(&self, col: usize, row: usize, is_boundary: B) -> HashSet<(usize, usize)>
+    where
+        B: Fn(&T) -> bool,
+    {
+        let mut to_flood = vec![(col, row)];
+        let mut flooded = HashSet::new();
+
+        while let Some((col, row)) = to_flood.pop() {
+            if is_boundary(&self[(col, row)]) || !flooded.insert((col, row)) {
+                continue;
+            }
+
+            to_flood.extend(
+                self.neighbors4(col, row)
+                    .map(|(col, row, _)| (col, row))
+                    .filter(|pos| !flooded.contains(pos)),
+            );
+        }
+
+        flooded
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (col, row): (usize, usize)) -> &T {
+        self.get(col, row)
+            .unwrap_or_else(|| panic!("({}, {}) out of bounds of {}x{} grid", col, row, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (col, row): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(col, row)
+            .unwrap_or_else(|| panic!("({}, {}) out of bounds of {}x{} grid", col, row, width, height))
+    }
+}
+
+/// Writes the cells row by row. A width given in the format applies to every cell,
+/// with cells separated by spaces, e.g. `{:2}`.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for (i, cell) in row.iter().enumerate() {
+                match f.width() {
+                    Some(width) => {
+                        if i > 0 {
+                            f.write_str(" ")?;
+                        }
+                        write!(f, "{:>width$}", cell, width = width)?;
+                    }
+                    None => write!(f, "{}", cell)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn grid_access() {
+        let grid = Grid::parse("123\n456\n", "a digit", |ch| ch.to_digit(10)).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.offset(0, 1, (1, -1)), Some((1, 0)));
+
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.transpose().row(2), [3, 6]);
+        assert_eq!(grid.crop(1, 1, 5, 5), Grid::from_rows([[5, 6]]));
+
+        let neighbors = |it: &mut dyn Iterator<Item = (usize, usize, &u32)>| it.map(|(_, _, &v)| v).collect::<Vec<_>>();
+        assert_eq!(neighbors(&mut grid.neighbors4(0, 0)), [2, 4]);
+        assert_eq!(neighbors(&mut grid.neighbors8(1, 0)), [1, 3, 4, 5, 6]);
+
+        assert_eq!(grid.map(|&v| v % 2).to_string(), "101\n010\n");
+        assert_eq!(format!("{:2}", grid), " 1  2  3\n 4  5  6\n");
+        assert_eq!(grid.flood_count(0, 0, |&v| v == 2), 5);
+    }
+}
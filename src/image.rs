@@ -0,0 +1,604 @@
+//! Export of grid frames to image files: PPM and PNG stills, and animated GIFs
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::viz::{Content, Frame};
+
+pub type Rgb = [u8; 3];
+
+/// Number of distinct shades a palette is quantized to, keeping GIFs within 256 colours
+const SHADES: usize = 250;
+
+/// Maps grid values to colours by interpolating between colour stops
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    stops: Vec<Rgb>,
+    /// Colour of highlighted cells
+    pub highlight: Rgb,
+    /// Colour of blank cells
+    pub blank: Rgb,
+}
+
+impl Palette {
+    /// Constructs a palette interpolating between the given colours, from the lowest value
+    /// to the highest.
+    ///
+    /// # Panics
+    /// Panics if there are no colours.
+    pub fn new(stops: Vec<Rgb>) -> Self {
+        assert!(!stops.is_empty(), "a palette needs at least one colour");
+
+        Self {
+            stops,
+            highlight: [255, 0, 255],
+            blank: [0, 0, 0],
+        }
+    }
+
+    pub fn gray() -> Self {
+        Self::new(vec![[16, 16, 16], [255, 255, 255]])
+    }
+
+    pub fn heat() -> Self {
+        Self {
+            highlight: [0, 160, 255],
+            ..Self::new(vec![[32, 0, 0], [200, 0, 0], [255, 160, 0], [255, 255, 200]])
+        }
+    }
+
+    pub fn ocean() -> Self {
+        Self {
+            highlight: [255, 200, 0],
+            ..Self::new(vec![[0, 16, 64], [0, 96, 160], [64, 200, 220], [240, 255, 255]])
+        }
+    }
+
+    pub fn viridis() -> Self {
+        Self::new(vec![[68, 1, 84], [59, 82, 139], [33, 145, 140], [94, 201, 98], [253, 231, 37]])
+    }
+
+    /// Returns the colour of `value` within the inclusive `range` of values
+    pub fn color(&self, value: u32, (min, max): (u32, u32)) -> Rgb {
+        let t = match max > min {
+            true => (value.clamp(min, max) - min) as f64 / (max - min) as f64,
+            false => 0.0,
+        };
+
+        let t = (t * (SHADES - 1) as f64).round() / (SHADES - 1) as f64;
+        let pos = t * (self.stops.len() - 1) as f64;
+
+        let i = (pos.floor() as usize).min(self.stops.len() - 1);
+        let j = (i + 1).min(self.stops.len() - 1);
+        let frac = pos - i as f64;
+
+        let mut color = [0; 3];
+        for (c, (a, b)) in color.iter_mut().zip(self.stops[i].iter().zip(self.stops[j])) {
+            *c = (*a as f64 + (b as f64 - *a as f64) * frac).round() as u8;
+        }
+        color
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::heat()
+    }
+}
+
+/// Parses a palette by its name (`gray`, `heat`, `ocean` or `viridis`) or as a list of
+/// comma-separated `#rrggbb` colours
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gray" | "grey" => Ok(Self::gray()),
+            "heat" => Ok(Self::heat()),
+            "ocean" => Ok(Self::ocean()),
+            "viridis" => Ok(Self::viridis()),
+            _ => {
+                let stops = s
+                    .split(',')
+                    .map(|color| {
+                        let hex = color.trim().strip_prefix('#').filter(|hex| hex.len() == 6);
+                        let channel = |i: usize| u8::from_str_radix(hex?.get(i..i + 2)?, 16).ok();
+
+                        Some([channel(0)?, channel(2)?, channel(4)?])
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| {
+                        format!("invalid palette `{}`, expected gray, heat, ocean, viridis or #rrggbb colours", s)
+                    })?;
+
+                Ok(Self::new(stops))
+            }
+        }
+    }
+}
+
+/// An RGB image
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Pixels, row by row
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Renders a grid frame with every cell drawn as a `scale` by `scale` square.
+    /// Returns `None` for text frames.
+    pub fn render(frame: &Frame, palette: &Palette, range: (u32, u32), scale: usize) -> Option<Self> {
+        let Content::Grid(grid) = &frame.content else {
+            return None;
+        };
+
+        let scale = scale.max(1);
+        let (width, height) = (grid.width() * scale, grid.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for (row, cells) in grid.rows().enumerate() {
+            let colors: Vec<Rgb> = cells
+                .iter()
+                .enumerate()
+                .map(|(col, &value)| {
+                    if frame.highlights.contains(&(col, row)) {
+                        palette.highlight
+                    } else if Some(value) == frame.blank {
+                        palette.blank
+                    } else {
+                        palette.color(value, range)
+                    }
+                })
+                .collect();
+
+            for _ in 0..scale {
+                pixels.extend(colors.iter().flat_map(|&color| std::iter::repeat_n(color, scale)));
+            }
+        }
+
+        Some(Self { width, height, pixels })
+    }
+
+    /// Writes the image in the binary PPM format
+    pub fn write_ppm<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.pixels.concat())
+    }
+
+    /// Writes the image as an uncompressed PNG
+    pub fn write_png<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+        png_chunk(&mut w, b"IHDR", &header)?;
+
+        let mut scanlines = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            scanlines.push(0);
+            scanlines.extend(row.concat());
+        }
+        png_chunk(&mut w, b"IDAT", &zlib_stored(&scanlines))?;
+
+        png_chunk(&mut w, b"IEND", &[])
+    }
+}
+
+fn png_chunk<W: Write>(w: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+
+    let crc = crc32(&[&kind[..], data].concat());
+    w.write_all(&crc.to_be_bytes())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+/// Wraps the data in a zlib stream of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        stream.push(last as u8);
+        stream.extend((block.len() as u16).to_le_bytes());
+        stream.extend((!(block.len() as u16)).to_le_bytes());
+        stream.extend(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    stream.extend(((b << 16) | a).to_be_bytes());
+
+    stream
+}
+
+/// Writes the images as the frames of a looping animated GIF, each shown for `delay`.
+/// The images must have the same size, of at most 65535 pixels a side, and at most 256 colours
+/// in total. Each image is compressed as soon as it's produced, so only one is held at a time.
+pub fn write_gif<W: Write>(
+    images: impl IntoIterator<Item = Image>,
+    delay: Duration,
+    mut w: W,
+) -> Result<(), Box<dyn Error>> {
+    let mut colors: HashMap<Rgb, u8> = HashMap::new();
+    let mut table = vec![];
+    let mut size = None;
+    let mut frames = vec![];
+
+    for image in images {
+        match size {
+            None => size = Some((image.width, image.height)),
+            Some(size) if size != (image.width, image.height) => {
+                return Err("all frames of an animation must have the same size".into())
+            }
+            Some(_) => {}
+        }
+
+        let mut indices = Vec::with_capacity(image.pixels.len());
+
+        for pixel in image.pixels {
+            let index = match colors.get(&pixel) {
+                Some(&index) => index,
+                None => {
+                    let index = u8::try_from(table.len()).map_err(|_| "too many colours for a GIF")?;
+                    colors.insert(pixel, index);
+                    table.push(pixel);
+                    index
+                }
+            };
+            indices.push(index);
+        }
+
+        frames.push(lzw(&indices));
+    }
+
+    let (width, height) = size.ok_or("no frames to animate")?;
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => (width.to_le_bytes(), height.to_le_bytes()),
+        _ => return Err(format!("a GIF can't be larger than {0}x{0} pixels, got {1}x{2}", u16::MAX, width, height).into()),
+    };
+
+    table.resize(256, [0; 3]);
+
+    w.write_all(b"GIF89a")?;
+    w.write_all(&width)?;
+    w.write_all(&height)?;
+    // Global colour table of 256 colours
+    w.write_all(&[0xf7, 0, 0])?;
+    w.write_all(&table.concat())?;
+    // Loop forever
+    w.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+    let delay = (delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+
+    for data in frames {
+        w.write_all(&[0x21, 0xf9, 4, 0])?;
+        w.write_all(&delay.to_le_bytes())?;
+        w.write_all(&[0, 0])?;
+
+        w.write_all(&[0x2c, 0, 0, 0, 0])?;
+        w.write_all(&width)?;
+        w.write_all(&height)?;
+        w.write_all(&[0, 8])?;
+
+        for block in data.chunks(255) {
+            w.write_all(&[block.len() as u8])?;
+            w.write_all(block)?;
+        }
+        w.write_all(&[0])?;
+    }
+
+    w.write_all(&[0x3b])?;
+    Ok(())
+}
+
+/// Packs codes of varying widths into bytes, least significant bits first
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += width;
+
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compresses 8-bit indices with the variable-width LZW flavour of GIFs
+fn lzw(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END: u16 = 257;
+    const MAX_CODE: u16 = 4095;
+
+    let mut out = BitWriter::default();
+    let mut width = 9;
+    let mut next = END + 1;
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+
+    // Widens the codes once the decoder, which lags one code behind, would need it
+    let widen = |width: u32, next: u16| match next >= 1 << width && width < 12 {
+        true => width + 1,
+        false => width,
+    };
+
+    out.write(CLEAR, width);
+
+    if let Some((&first, rest)) = indices.split_first() {
+        let mut prefix = first as u16;
+
+        for &index in rest {
+            if let Some(&code) = codes.get(&(prefix, index)) {
+                prefix = code;
+                continue;
+            }
+
+            out.write(prefix, width);
+            width = widen(width, next);
+
+            if next >= MAX_CODE {
+                // Start over with an empty table and the narrowest codes
+                out.write(CLEAR, width);
+                codes.clear();
+                next = END + 1;
+                width = 9;
+            } else {
+                codes.insert((prefix, index), next);
+                next += 1;
+            }
+
+            prefix = index as u16;
+        }
+
+        out.write(prefix, width);
+        width = widen(width, next);
+    }
+
+    out.write(END, width);
+    out.finish()
+}
+
+/// Settings of exporting frames to an image file
+#[derive(Clone, Debug)]
+pub struct Export {
+    /// Output file, whose extension (`ppm`, `png` or `gif`) selects the format
+    pub path: PathBuf,
+    pub palette: Palette,
+    /// Size of a grid cell in pixels
+    pub scale: usize,
+    /// Time each frame of an animation is shown for
+    pub delay: Duration,
+}
+
+impl Export {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            palette: Palette::default(),
+            scale: 4,
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    /// Returns the settings with the path suffixed with `suffix` before the extension,
+    /// e.g. `basins-part2.png`
+    pub fn with_suffix(&self, suffix: &str) -> Self {
+        let mut name = self.path.file_stem().unwrap_or_default().to_owned();
+        name.push(suffix);
+        if let Some(extension) = self.path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+
+        Self {
+            path: self.path.with_file_name(name),
+            ..self.clone()
+        }
+    }
+}
+
+/// Writes the grid frames to an image file. GIFs animate all frames, while PPM and PNG
+/// stills show the last one. Colours are scaled to the range of values across all frames.
+pub fn export(frames: &[Frame], export: &Export) -> Result<(), Box<dyn Error>> {
+    let grids: Vec<&Frame> = frames
+        .iter()
+        .filter(|frame| matches!(frame.content, Content::Grid(_)))
+        .collect();
+
+    let range = grids
+        .iter()
+        .filter_map(|frame| match &frame.content {
+            Content::Grid(grid) => Some(grid.iter().filter(move |&&v| Some(v) != frame.blank)),
+            Content::Text(_) => None,
+        })
+        .flatten()
+        .fold(None, |range, &v| match range {
+            Some((min, max)) => Some((v.min(min), v.max(max))),
+            None => Some((v, v)),
+        })
+        .unwrap_or((0, 0));
+
+    let render = |frame: &Frame| Image::render(frame, &export.palette, range, export.scale).unwrap();
+
+    let extension = export.path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let w = || -> io::Result<_> { Ok(BufWriter::new(File::create(&export.path)?)) };
+
+    match extension {
+        "gif" => {
+            write_gif(grids.iter().map(|&frame| render(frame)), export.delay, w()?)?;
+        }
+        "png" | "ppm" => {
+            let last = grids.last().ok_or("no grid frames to export")?;
+            match extension {
+                "png" => render(last).write_png(w()?)?,
+                _ => render(last).write_ppm(w()?)?,
+            }
+        }
+        _ => return Err(format!("unknown image format of {}, expected .ppm, .png or .gif", export.path.display()).into()),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn palettes() {
+        let palette: Palette = "#000000,#ff0080".parse().unwrap();
+        assert_eq!(palette.color(0, (0, 10)), [0, 0, 0]);
+        assert_eq!(palette.color(10, (0, 10)), [255, 0, 128]);
+        assert_eq!(palette.color(20, (0, 10)), [255, 0, 128]);
+        assert!("#12345".parse::<Palette>().is_err());
+        assert_eq!("viridis".parse::<Palette>(), Ok(Palette::viridis()));
+    }
+
+    #[test]
+    fn encode_images() {
+        let frame = Frame::grid("", Grid::from_rows([[0, 1], [2, 3]])).with_highlights([(1, 1)]);
+        let image = Image::render(&frame, &Palette::gray(), (0, 2), 2).unwrap();
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[15], Palette::gray().highlight);
+
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+
+        let mut png = vec![];
+        image.write_png(&mut png).unwrap();
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+        let mut gif = vec![];
+        write_gif([image.clone(), image], Duration::from_millis(50), &mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a\x04\x00\x04\x00"));
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        let wide = Image {
+            width: 65536,
+            height: 1,
+            pixels: vec![[0; 3]; 65536],
+        };
+        assert!(write_gif([wide], Duration::from_millis(50), &mut vec![]).is_err());
+    }
+
+    /// Decodes GIF LZW data, as a reference for the encoder, returning it along with the number
+    /// of times the code table was cleared
+    fn unlzw(data: &[u8]) -> (Vec<u8>, usize) {
+        let bits = data.iter().flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1));
+        let mut bits = bits.collect::<Vec<_>>().into_iter();
+        let mut read = |width| (0..width).map(|i| (bits.next().unwrap() as u16) << i).sum::<u16>();
+
+        let mut out = vec![];
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut width = 9;
+        let mut prev: Option<Vec<u8>> = None;
+        let mut clears = 0;
+
+        loop {
+            let code = read(width);
+            match code {
+                256 => {
+                    table = (0..=255).map(|i| vec![i]).chain([vec![], vec![]]).collect();
+                    width = 9;
+                    prev = None;
+                    clears += 1;
+                }
+                257 => return (out, clears),
+                _ => {
+                    let entry = match table.get(code as usize) {
+                        Some(entry) => entry.clone(),
+                        None => {
+                            let prev = prev.clone().unwrap();
+                            [&prev[..], &prev[..1]].concat()
+                        }
+                    };
+
+                    if let Some(prev) = prev {
+                        table.push([&prev[..], &entry[..1]].concat());
+                    }
+
+                    out.extend(&entry);
+                    prev = Some(entry);
+
+                    if table.len() == 1 << width && width < 12 {
+                        width += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        // Xorshift bytes, which barely compress and so fill the code table several times over
+        let mut state = 0x2545_f491u32;
+        let data: Vec<u8> = (0..50_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+
+        let (decoded, clears) = unlzw(&lzw(&data));
+        assert_eq!(decoded, data);
+        assert!(clears > 1, "the code table was never cleared");
+
+        let runs: Vec<u8> = (0..50_000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 29) as u8).collect();
+        assert_eq!(unlzw(&lzw(&runs)).0, runs);
+        assert_eq!(unlzw(&lzw(&[])), (vec![], 1));
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// Directory where `aoc.py` stores the fetched inputs
+pub const INPUTS_DIR: &str = "inputs";
+
+/// Returns the path of the input (or the example input) for the given day,
+/// following the layout used by `aoc.py`
+pub fn input_path(day: u32, example: bool) -> PathBuf {
+    let suffix = if example { "-example" } else { "" };
+    PathBuf::from(INPUTS_DIR).join(format!("day{}{}.txt", day, suffix))
+}
+
+/// Returns the path of one of several example inputs for the given day
+pub fn numbered_example_path(day: u32, index: usize) -> PathBuf {
+    PathBuf::from(INPUTS_DIR).join(format!("day{}-example-{}.txt", day, index))
+}
+
+/// Where to read the puzzle input from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    /// The input stored for a day in [`INPUTS_DIR`]
+    Day { day: u32, example: bool },
+    Stdin,
+    /// Input given directly on the command line
+    Inline(String),
+}
+
+impl InputSource {
+    /// Interprets a command line argument, where `-` stands for the standard input
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Self::Stdin,
+            _ => Self::File(arg.into()),
+        }
+    }
+
+    pub fn read(&self) -> Result<String, Box<dyn Error>> {
+        match self {
+            Self::File(path) => {
+                fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e).into())
+            }
+            &Self::Day { day, example } => {
+                let path = input_path(day, example);
+
+                fs::read_to_string(&path).map_err(|e| match e.kind() {
+                    io::ErrorKind::NotFound => format!(
+                        "no {} for day {}, expected it at {}",
+                        if example { "example input" } else { "input" },
+                        day,
+                        path.display(),
+                    )
+                    .into(),
+                    _ => format!("{}: {}", path.display(), e).into(),
+                })
+            }
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+            Self::Inline(input) => Ok(input.clone()),
+        }
+    }
+}
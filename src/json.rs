@@ -0,0 +1,153 @@
+use std::fmt::{self, Write};
+
+/// A JSON value which can be written out compactly with [`fmt::Display`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number, stored already formatted
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// An object with its keys kept in insertion order
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object() -> Self {
+        Self::Object(vec![])
+    }
+
+    /// Adds a key to an object.
+    ///
+    /// # Panics
+    /// Panics if this value is not an object.
+    pub fn with(mut self, key: &str, value: impl Into<Json>) -> Self {
+        match &mut self {
+            Self::Object(entries) => entries.push((key.to_owned(), value.into())),
+            _ => panic!("not a JSON object"),
+        }
+        self
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n: $t) -> Self {
+                    Self::Number(n.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(u8, u32, u64, u128, usize, i32, i64, isize);
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Self {
+        if n.is_finite() {
+            Self::Number(n.to_string())
+        } else {
+            Self::Null
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(values: Vec<T>) -> Self {
+        Self::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => f.write_char(ch)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Number(n) => f.write_str(n),
+            Self::String(s) => write_str(f, s),
+            Self::Array(values) => {
+                f.write_char('[')?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_char(']')
+            }
+            Self::Object(entries) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_str(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let json = Json::object()
+            .with("day", 2u32)
+            .with("answer", "a \"quoted\"\n\u{1}")
+            .with("values", vec![1.5, f64::NAN])
+            .with("missing", None::<u32>);
+
+        assert_eq!(
+            json.to_string(),
+            r#"{"day":2,"answer":"a \"quoted\"\n\u0001","values":[1.5,null],"missing":null}"#,
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Solutions to [Advent of Code 2021](https://adventofcode.com/2021) puzzles, along with
+//! utilities for running, benchmarking and verifying them
+
+pub mod answers;
+pub mod bench;
+pub mod bigint;
+pub mod fetch;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod json;
+pub mod matrix;
+pub mod page;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod table;
+pub mod viz;
+
+use solution::DynSolution;
+
+// Day modules and their registry, generated by the build script from `src/day_N.rs` files
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+/// Looks up the solution for the given day
+pub fn solution(day: u32) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .find(|&&(d, _)| d == day)
+        .map(|&(_, solution)| solution)
+}
@@ -0,0 +1,33 @@
+//! Logger printing the diagnostics of solutions to the standard error
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Prints records of this crate up to the maximum level, and warnings and errors
+/// of dependencies
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.target().starts_with("aoc_2021") || metadata.level() <= Level::Warn)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let target = record.target();
+            let target = target.strip_prefix("aoc_2021::").unwrap_or(target);
+
+            eprintln!("[{} {}] {}", record.level(), target, record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+
+/// Installs the logger with the given maximum level
+pub(crate) fn init(level: LevelFilter) {
+    log::set_logger(&LOGGER).expect("logger installed twice");
+    log::set_max_level(level);
+}
@@ -1,63 +1,82 @@
-#![feature(array_windows)]
-#![feature(mixed_integer_ops)]
-#![feature(int_abs_diff)]
-#![feature(int_log)]
-#![feature(never_type)]
-
-use std::{error::Error, env, fs::File, io::Read};
-
-#[cfg(feature = "day_1")]
-mod day_1;
-#[cfg(feature = "day_2")]
-mod day_2;
-#[cfg(feature = "day_3")]
-mod day_3;
-#[cfg(feature = "day_4")]
-mod day_4;
-#[cfg(feature = "day_5")]
-mod day_5;
-#[cfg(feature = "day_6")]
-mod day_6;
-#[cfg(feature = "day_7")]
-mod day_7;
-#[cfg(feature = "day_8")]
-mod day_8;
-#[cfg(feature = "day_9")]
-mod day_9;
-#[cfg(feature = "day_10")]
-mod day_10;
-// Include new day mod here
-
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut args = env::args();
-    let _ = args.next().unwrap();
-    let filename = args.next().unwrap();
-
-    let mut file = File::open(filename)?;
-    let mut input = String::new();
-    file.read_to_string(&mut input)?;
-
-    #[cfg(feature = "day_1")]
-    day_1::main(&input[..])?;
-    #[cfg(feature = "day_2")]
-    day_2::main(&input[..])?;
-    #[cfg(feature = "day_3")]
-    day_3::main(&input[..])?;
-    #[cfg(feature = "day_4")]
-    day_4::main(&input[..])?;
-    #[cfg(feature = "day_5")]
-    day_5::main(&input[..])?;
-    #[cfg(feature = "day_6")]
-    day_6::main(&input[..])?;
-    #[cfg(feature = "day_7")]
-    day_7::main(&input[..])?;
-    #[cfg(feature = "day_8")]
-    day_8::main(&input[..])?;
-    #[cfg(feature = "day_9")]
-    day_9::main(&input[..])?;
-    #[cfg(feature = "day_10")]
-    day_10::main(&input[..])?;
-    // Call new day here
+use std::{env, error::Error, fs, path::Path, process};
+
+use aoc_2021::{
+    answers::Answers,
+    bench,
+    fetch::{self, Client},
+    page, runner, scaffold,
+    solution::Part,
+};
+use cli::Command;
+
+mod cli;
+mod logger;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    logger::init(cli::verbosity(&mut args));
+
+    match Command::parse(args)? {
+        Command::Run { day, part, input, format, visualize, export, report, params } => {
+            let solution = aoc_2021::solution(day).ok_or(format!("day {} is not implemented", day))?;
+
+            let parts = match part {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+
+            if let Some(report) = report {
+                runner::report_day(day, solution, &input, &report, &params)?;
+            } else if visualize.is_some() || export.is_some() {
+                runner::visualize_day(day, solution, &parts, &input, &params, visualize, export.as_ref())?;
+            } else {
+                runner::run_day(day, solution, &parts, &input, &params, format)?;
+            }
+        }
+        Command::All { example, format } => runner::run_all(example, format)?,
+        Command::Bench { day, input, runs, report } => {
+            let solution = aoc_2021::solution(day).ok_or(format!("day {} is not implemented", day))?;
+            bench::bench_day(day, solution, &input, runs, report.as_deref())?;
+        }
+        Command::Verify { day, answers, example } => {
+            runner::verify(&Answers::load(&answers)?, day, example)?
+        }
+        Command::New { day } => {
+            let path = scaffold::new_day(Path::new("."), day)?;
+            println!("Created {}", path.display());
+            println!("Add its example input and expected answers to inputs/ for the example tests to pass");
+        }
+        Command::Fetch { year, day, base_url } => {
+            let root = Path::new(".");
+            let client = Client::from_config(root, base_url.as_deref());
+
+            match fetch::fetch_input(&client, root, year, day)? {
+                (path, true) => println!("Fetched {}", path.display()),
+                (path, false) => println!("{} already exists, not downloading", path.display()),
+            }
+        }
+        Command::Examples { year, day, page, base_url, save } => {
+            let html = match page {
+                Some(page) => fs::read_to_string(&page).map_err(|e| format!("{}: {}", page.display(), e))?,
+                None => Client::from_config(Path::new("."), base_url.as_deref()).puzzle_page(year, day)?,
+            };
+
+            let blocks = page::code_blocks(&html);
+            print!("{}", page::preview(&blocks));
+
+            if let Some(index) = save {
+                let path = page::save_example(Path::new("."), day, &blocks, index)?;
+                println!("Saved [{}] to {}", index, path.display());
+            }
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,178 @@
+//! Square matrices over arbitrary number types, for advancing linear recurrences many steps at once
+//! by exponentiation
+
+use std::fmt;
+
+use crate::bigint::BigUint;
+
+/// Numbers which can be added and multiplied, making up the cells of a [`Matrix`]
+pub trait Semiring: Clone {
+    fn add(&self, other: &Self) -> Self;
+
+    fn mul(&self, other: &Self) -> Self;
+
+    /// Returns the approximate magnitude of the number, e.g. for plotting
+    fn to_f64(&self) -> f64;
+}
+
+impl Semiring for BigUint {
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self * other
+    }
+
+    fn to_f64(&self) -> f64 {
+        BigUint::to_f64(self)
+    }
+}
+
+/// An integer modulo some number, for when only the remainder of an enormous count is needed
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Modular {
+    value: u64,
+    modulus: u64,
+}
+
+impl Modular {
+    /// # Panics
+    /// Panics if the modulus is 0.
+    pub fn new(value: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+
+        Self {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+}
+
+impl Semiring for Modular {
+    fn add(&self, other: &Self) -> Self {
+        let sum = (self.value as u128 + other.value as u128) % self.modulus as u128;
+        Self { value: sum as u64, ..*self }
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let product = self.value as u128 * other.value as u128 % self.modulus as u128;
+        Self { value: product as u64, ..*self }
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.value as f64
+    }
+}
+
+impl fmt::Display for Modular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+/// A square matrix
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Matrix<T> {
+    /// Cells in row-major order
+    cells: Vec<T>,
+    size: usize,
+}
+
+impl<T: Semiring> Matrix<T> {
+    /// Creates a matrix of the given size with cells computed from their `(row, column)`
+    pub fn from_fn(size: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..size * size).map(|i| f(i / size, i % size)).collect();
+        Self { cells, size }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.cells[row * self.size + col]
+    }
+
+    /// Returns the sum of `f(i)` for `i` in `0..n`, which must not be empty
+    fn sum(n: usize, f: impl Fn(usize) -> T) -> T {
+        (1..n).fold(f(0), |acc, i| acc.add(&f(i)))
+    }
+
+    /// # Panics
+    /// Panics if the matrices differ in size, or are empty.
+    pub fn mul(&self, other: &Self) -> Self {
+        assert_eq!(self.size, other.size, "matrices differ in size");
+
+        Self::from_fn(self.size, |row, col| {
+            Self::sum(self.size, |k| self.get(row, k).mul(other.get(k, col)))
+        })
+    }
+
+    /// Multiplies the matrix by a column vector
+    ///
+    /// # Panics
+    /// Panics if the vector's length differs from the matrix size, or the matrix is empty.
+    pub fn apply(&self, vector: &[T]) -> Vec<T> {
+        assert_eq!(self.size, vector.len(), "vector length differs from matrix size");
+
+        (0..self.size)
+            .map(|row| Self::sum(self.size, |k| self.get(row, k).mul(&vector[k])))
+            .collect()
+    }
+
+    /// Raises the matrix to the given power by repeated squaring, in `O(log exp)` multiplications.
+    /// `identity` is returned for an exponent of 0.
+    pub fn pow(&self, mut exp: u64, identity: Self) -> Self {
+        let mut result = identity;
+        let mut square = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&square);
+            }
+
+            exp >>= 1;
+            if exp > 0 {
+                square = square.mul(&square);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fibonacci() {
+        let fib = |n: u64, one: BigUint, zero: BigUint| {
+            let step = Matrix::from_fn(2, |row, col| if row + col < 2 { one.clone() } else { zero.clone() });
+            let identity = Matrix::from_fn(2, |row, col| if row == col { one.clone() } else { zero.clone() });
+            step.pow(n, identity).get(0, 1).clone()
+        };
+
+        assert_eq!(fib(0, 1.into(), 0.into()).to_string(), "0");
+        assert_eq!(fib(10, 1.into(), 0.into()).to_string(), "55");
+        assert_eq!(fib(100, 1.into(), 0.into()).to_string(), "354224848179261915075");
+
+        let m = 1_000_000_007;
+        let step = Matrix::from_fn(2, |row, col| Modular::new((row + col < 2) as u64, m));
+        let identity = Matrix::from_fn(2, |row, col| Modular::new((row == col) as u64, m));
+        // F(100) mod 10^9 + 7
+        assert_eq!(step.pow(100, identity).get(0, 1).value(), 687995182);
+
+        let v = step.apply(&[Modular::new(1, m), Modular::new(0, m)]);
+        assert_eq!(v.iter().map(|x| x.value()).collect::<Vec<_>>(), [1, 1]);
+    }
+}
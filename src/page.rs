@@ -0,0 +1,197 @@
+//! Extraction of example inputs from puzzle description pages
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::input::numbered_example_path;
+
+/// Number of lines of each code block shown by [`preview`]
+const PREVIEW_LINES: usize = 3;
+/// Number of characters of each line shown by [`preview`]
+const PREVIEW_WIDTH: usize = 60;
+
+/// Returns the text contents of all `<pre><code>` blocks in the HTML page, in order,
+/// with inner tags (e.g. `<em>`) stripped and character references decoded
+pub fn code_blocks(html: &str) -> Vec<String> {
+    let mut blocks = vec![];
+    let mut rest = html;
+
+    while let Some(start) = find_tag(rest, "pre") {
+        rest = &rest[start..];
+
+        // The `<code>` tag must directly follow `<pre>`, save for whitespace
+        let after_pre = &rest[rest.find('>').map_or(rest.len(), |i| i + 1)..];
+        let trimmed = after_pre.trim_start();
+
+        if find_tag(trimmed, "code") != Some(0) {
+            rest = after_pre;
+            continue;
+        }
+
+        let content = &trimmed[trimmed.find('>').map_or(trimmed.len(), |i| i + 1)..];
+        let end = content.find("</code>").unwrap_or(content.len());
+
+        blocks.push(decode_entities(&strip_tags(&content[..end])));
+        rest = &content[end..];
+    }
+
+    blocks
+}
+
+/// Lists the code blocks with their 1-based indices and line counts, along with their first few
+/// lines cut to a readable width, for picking one to save with [`save_example`]
+pub fn preview(blocks: &[String]) -> String {
+    let mut listing = String::new();
+
+    for (i, block) in blocks.iter().enumerate() {
+        listing += &format!("[{}] {} line(s)\n", i + 1, block.lines().count());
+        for line in block.lines().take(PREVIEW_LINES) {
+            listing += &format!("    {}\n", line.chars().take(PREVIEW_WIDTH).collect::<String>());
+        }
+    }
+
+    listing
+}
+
+/// Saves the code block with the given 1-based index as a numbered example input for the day
+/// in `inputs/` under `root`, returning its path
+pub fn save_example(root: &Path, day: u32, blocks: &[String], index: usize) -> Result<PathBuf, Box<dyn Error>> {
+    let block = index
+        .checked_sub(1)
+        .and_then(|i| blocks.get(i))
+        .ok_or(format!("no code block [{}], the page has {}", index, blocks.len()))?;
+
+    let path = root.join(numbered_example_path(day, index));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, block)?;
+
+    Ok(path)
+}
+
+/// Finds the start of the next opening tag with the given name
+fn find_tag(html: &str, name: &str) -> Option<usize> {
+    let mut offset = 0;
+
+    while let Some(i) = html[offset..].find('<') {
+        let start = offset + i;
+        let tag = &html[start + 1..];
+
+        if tag.starts_with(name)
+            && tag[name.len()..].starts_with(|ch: char| ch == '>' || ch.is_ascii_whitespace())
+        {
+            return Some(start);
+        }
+
+        offset = start + 1;
+    }
+
+    None
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => (),
+        }
+    }
+
+    text
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&len| len <= 10)
+            .map(|len| (&rest[1..len + 1], len + 2));
+
+        let ch = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => name.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (ch, entity) {
+            (Some(ch), Some((_, len))) => {
+                decoded.push(ch);
+                rest = &rest[len..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_code_blocks() {
+        let html = r#"
+            <p>For example:</p>
+            <pre><code>3,4,3,1,2
+</code></pre>
+            <p>Inline <code>code</code> is ignored.</p>
+            <pre class="wide">
+              <code>a &lt;-&gt; <em>b</em> &amp;&#39;c&#x27; &unknown; &
+</code></pre>
+            <pre>no code</pre>
+        "#;
+
+        assert_eq!(code_blocks(html), vec!["3,4,3,1,2\n", "a <-> b &'c' &unknown; &\n"]);
+    }
+
+    #[test]
+    fn preview_and_save() {
+        let blocks = vec!["1\n2\n3\n4\n".to_owned(), "x".repeat(80)];
+        assert_eq!(
+            preview(&blocks),
+            format!("[1] 4 line(s)\n    1\n    2\n    3\n[2] 1 line(s)\n    {}\n", "x".repeat(60))
+        );
+
+        let root = std::env::temp_dir().join(format!("aoc-2021-page-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let path = save_example(&root, 6, &blocks, 1).unwrap();
+        assert_eq!(path, root.join("inputs/day6-example-1.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), blocks[0]);
+
+        assert!(save_example(&root, 6, &blocks, 0).is_err());
+        assert!(save_example(&root, 6, &blocks, 3).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
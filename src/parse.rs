@@ -0,0 +1,257 @@
+//! Error-reporting helpers for the usual shapes of puzzle inputs
+
+use std::{error::Error, fmt, str::FromStr};
+
+/// An error in the puzzle input, pinpointing the offending text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The day whose input was being parsed, if known
+    pub day: Option<u32>,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number, counted in characters
+    pub column: usize,
+    /// The offending part of the line
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Constructs an error for the `span` of the line at the given 0-based index.
+    /// The column is computed from the position of `span` within `line`, so `span` should
+    /// be a subslice of `line`. Otherwise, the column is reported as 1.
+    pub fn new(index: usize, line: &str, span: &str, message: impl Into<String>) -> Self {
+        let offset = (span.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+
+        let column = match line.get(..offset) {
+            Some(prefix) if offset + span.len() <= line.len() => prefix.chars().count() + 1,
+            _ => 1,
+        };
+
+        Self {
+            day: None,
+            line: index + 1,
+            column,
+            text: span.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    /// Constructs an error for the whole input, e.g. when it's empty
+    pub fn input(message: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line: 1,
+            column: 1,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+
+    pub fn with_day(mut self, day: u32) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)?;
+
+        if !self.text.is_empty() {
+            write!(f, " (found `{}`)", self.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// A non-blank line of the input, trimmed, remembering its position for error reporting
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    /// 0-based index of the line in the input
+    pub index: usize,
+    pub text: &'a str,
+    full: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(index: usize, full: &'a str) -> Self {
+        Self { index, text: full.trim(), full }
+    }
+
+    /// Constructs an error for a `span` of this line
+    pub fn error(&self, span: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.index, self.full, span, message)
+    }
+
+    /// Parses a `span` of this line, describing it as `what` on failure
+    pub fn value<T: FromStr>(&self, span: &'a str, what: &str) -> Result<T, ParseError> {
+        span.trim()
+            .parse()
+            .map_err(|_| self.error(span, format!("expected {}", what)))
+    }
+
+    /// Parses the whole line as a single value
+    pub fn parse<T: FromStr>(&self, what: &str) -> Result<T, ParseError> {
+        self.value(self.text, what)
+    }
+
+    /// Parses a list of values separated by `sep`, e.g. `3,4,3,1,2`
+    pub fn list<T: FromStr>(&self, sep: &str, what: &str) -> Result<Vec<T>, ParseError> {
+        self.text.split(sep).map(|span| self.value(span, what)).collect()
+    }
+
+    /// Parses a list of values separated by whitespace from a `span` of this line
+    pub fn fields<T: FromStr>(&self, span: &'a str, what: &str) -> Result<Vec<T>, ParseError> {
+        span.split_ascii_whitespace()
+            .map(|span| self.value(span, what))
+            .collect()
+    }
+
+    /// Splits a `span` of this line around `sep`, describing the expected `shape` on failure
+    pub fn split_once(&self, span: &'a str, sep: &str, shape: &str) -> Result<(&'a str, &'a str), ParseError> {
+        span.split_once(sep)
+            .ok_or_else(|| self.error(span, format!("expected `{}`", shape)))
+    }
+
+    /// Parses a coordinate pair like `x,y` from a `span` of this line
+    pub fn pair<T: FromStr>(&self, span: &'a str, what: &str) -> Result<(T, T), ParseError> {
+        let (x, y) = self.split_once(span, ",", "x,y")?;
+        Ok((self.value(x, what)?, self.value(y, what)?))
+    }
+
+    /// Parses a command like `forward 5` into its keyword and argument
+    pub fn command<T: FromStr>(&self, what: &str) -> Result<(&'a str, T), ParseError> {
+        let (key, value) = self.split_once(self.text, " ", "<command> <value>")?;
+        Ok((key, self.value(value, what)?))
+    }
+
+    /// Finds the first character in a `span` of this line not accepted by `f`,
+    /// returning it as a span for errors
+    pub fn find_invalid<F>(&self, span: &'a str, f: F) -> Option<&'a str>
+    where
+        F: Fn(char) -> bool,
+    {
+        span.char_indices()
+            .find(|&(_, ch)| !f(ch))
+            .map(|(pos, ch)| &span[pos..pos + ch.len_utf8()])
+    }
+}
+
+/// Iterates over the non-blank lines of the input
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Line::new(index, line))
+        .filter(|line| !line.text.is_empty())
+}
+
+/// Returns the only non-blank line of the input
+pub fn single_line(input: &str) -> Result<Line<'_>, ParseError> {
+    let mut lines = lines(input);
+    let line = lines.next().ok_or_else(|| ParseError::input("empty input"))?;
+
+    match lines.next() {
+        Some(extra) => Err(extra.error(extra.text, "expected a single line")),
+        None => Ok(line),
+    }
+}
+
+/// Splits the input into blocks of non-blank lines separated by blank lines
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = vec![];
+    let mut section = vec![];
+
+    for (index, line) in input.lines().enumerate() {
+        let line = Line::new(index, line);
+
+        if !line.text.is_empty() {
+            section.push(line);
+        } else if !section.is_empty() {
+            sections.push(std::mem::take(&mut section));
+        }
+    }
+
+    if !section.is_empty() {
+        sections.push(section);
+    }
+
+    sections
+}
+
+/// Parses a rectangular grid of characters, converting each with `f`, which rejects
+/// a character by returning `None`. `what` describes the accepted characters.
+pub fn char_grid<T, F>(input: &str, what: &str, f: F) -> Result<Vec<Vec<T>>, ParseError>
+where
+    F: Fn(char) -> Option<T>,
+{
+    let mut rows: Vec<Vec<T>> = vec![];
+
+    for line in lines(input) {
+        let row = line
+            .text
+            .char_indices()
+            .map(|(pos, ch)| {
+                f(ch).ok_or_else(|| line.error(&line.text[pos..pos + ch.len_utf8()], format!("expected {}", what)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                let message = format!("expected {} characters like the previous lines", first.len());
+                return Err(line.error(line.text, message));
+            }
+        }
+
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn column_from_span() {
+        let line = "0,9 -> ű,9";
+        let span = &line[7..9];
+
+        let e = ParseError::new(2, line, span, "expected a number").with_day(5);
+        assert_eq!((e.line, e.column, &e.text[..]), (3, 8, "ű"));
+        assert_eq!(e.to_string(), "day 5, line 3, column 8: expected a number (found `ű`)");
+
+        let e = ParseError::new(0, line, "elsewhere", "oops");
+        assert_eq!(e.column, 1);
+    }
+
+    #[test]
+    fn helpers() {
+        let input = "3,4\n\n  forward x\n0,9 -> 5,9\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 2);
+
+        let [list] = &sections[0][..] else { panic!() };
+        assert_eq!(list.list::<u32>(",", "a number"), Ok(vec![3, 4]));
+
+        let [command, segment] = &sections[1][..] else { panic!() };
+        let e = command.command::<i64>("a number").unwrap_err();
+        assert_eq!((e.line, e.column, &e.text[..]), (3, 11, "x"));
+
+        let (a, _) = segment.split_once(segment.text, " -> ", "a -> b").unwrap();
+        assert_eq!(segment.pair::<u32>(a, "a coordinate"), Ok((0, 9)));
+
+        let e = char_grid(" 12\n3\n", "a digit", |ch| ch.to_digit(10)).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+        assert!(single_line(input).is_err());
+    }
+}
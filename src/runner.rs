@@ -0,0 +1,285 @@
+use std::{
+    any::Any,
+    error::Error,
+    fs, io,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::Answers,
+    image::{self, Export},
+    input::{input_path, InputSource},
+    json::Json,
+    solution::{Answer, DynSolution, Params, Part},
+    table::Table,
+    viz::{self, Playback},
+    SOLUTIONS,
+};
+
+/// How answers are printed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// Human-readable output
+    Text,
+    /// One JSON record per line
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("invalid format `{}`, expected text or json", s)),
+        }
+    }
+}
+
+/// Builds the JSON record describing the outcome of solving one part
+fn record(day: u32, part: Part, answer: &Result<Answer, Box<dyn Error>>, duration: Duration) -> Json {
+    let record = Json::object()
+        .with("day", day)
+        .with("part", part.number());
+
+    let record = match answer {
+        Ok(answer) => record.with("answer", &answer.value[..]).with(
+            "values",
+            Json::Object(
+                answer
+                    .extra
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), Json::from(&value[..])))
+                    .collect(),
+            ),
+        ),
+        Err(e) => record.with("error", e.to_string()),
+    };
+
+    record.with("duration_ns", duration.as_nanos())
+}
+
+/// Reads and parses the input of a single day's solution, applying the given settings to it
+fn prepare(
+    day: u32,
+    solution: &dyn DynSolution,
+    input: &InputSource,
+    params: &Params,
+) -> Result<Box<dyn Any>, Box<dyn Error>> {
+    let mut input = solution
+        .parse_any(&input.read()?[..])
+        .map_err(|e| e.with_day(day))?;
+
+    solution.configure_any(&mut *input, params)?;
+    Ok(input)
+}
+
+/// Runs a single day's solution on the given input with optional settings, printing the answer
+/// for each part
+pub fn run_day(
+    day: u32,
+    solution: &dyn DynSolution,
+    parts: &[Part],
+    input: &InputSource,
+    params: &Params,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let input = prepare(day, solution, input, params)?;
+
+    for &part in parts {
+        let start = Instant::now();
+        let answer = solution.solve_any(&*input, part);
+        let elapsed = start.elapsed();
+
+        match format {
+            Format::Text => println!("{}", answer?),
+            Format::Json => {
+                println!("{}", record(day, part, &answer, elapsed));
+                answer?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a single day's solution like [`run_day`], recording the frames emitted while solving
+/// each part. Before printing the answer, the frames are played back and/or exported to an image,
+/// with the part number added to the image name when exporting more than one part.
+pub fn visualize_day(
+    day: u32,
+    solution: &dyn DynSolution,
+    parts: &[Part],
+    input: &InputSource,
+    params: &Params,
+    playback: Option<Playback>,
+    export: Option<&Export>,
+) -> Result<(), Box<dyn Error>> {
+    let input = prepare(day, solution, input, params)?;
+
+    for &part in parts {
+        let (answer, frames) = viz::record(|| solution.solve_any(&*input, part));
+
+        if frames.is_empty() {
+            log::warn!("day {} part {} has no visualisation", day, part);
+        }
+
+        if let Some(playback) = playback {
+            viz::play(&frames, playback)?;
+        }
+
+        if let Some(export) = export {
+            let export = match parts.len() {
+                1 => export.clone(),
+                _ => export.with_suffix(&format!("-part{}", part)),
+            };
+
+            image::export(&frames, &export)?;
+            log::info!("saved {}", export.path.display());
+        }
+
+        println!("{}", answer?);
+    }
+
+    Ok(())
+}
+
+/// Prints the named report of a single day's solution on the given input
+pub fn report_day(
+    day: u32,
+    solution: &dyn DynSolution,
+    input: &InputSource,
+    name: &str,
+    params: &Params,
+) -> Result<(), Box<dyn Error>> {
+    if !solution.reports().contains(&name) {
+        let available = match solution.reports() {
+            [] => "none".to_owned(),
+            reports => reports.join(", "),
+        };
+
+        return Err(format!("day {} has no `{}` report, available: {}", day, name, available).into());
+    }
+
+    let input = prepare(day, solution, input, params)?;
+
+    print!("{}", solution.report_any(&*input, name, params)?);
+    Ok(())
+}
+
+/// Runs both parts of every registered day on its input (or example input) from `inputs/`,
+/// printing a summary table. Days without an input file are skipped.
+pub fn run_all(example: bool, format: Format) -> Result<(), Box<dyn Error>> {
+    let mut table = Table::new(["Day", "Part", "Answer", "Time"]);
+
+    for &(day, solution) in SOLUTIONS {
+        let filename = input_path(day, example);
+
+        let skip = match fs::read_to_string(&filename) {
+            Ok(input) => match solution.parse_any(&input[..]).map_err(|e| e.with_day(day)) {
+                Ok(input) => {
+                    for part in Part::ALL {
+                        let start = Instant::now();
+                        let answer = solution.solve_any(&*input, part);
+                        let elapsed = start.elapsed();
+
+                        match format {
+                            Format::Text => table.row([
+                                day.to_string(),
+                                part.to_string(),
+                                answer.map_or_else(|e| format!("error: {}", e), |a| a.value),
+                                format!("{:.2?}", elapsed),
+                            ]),
+                            Format::Json => println!("{}", record(day, part, &answer, elapsed)),
+                        }
+                    }
+
+                    continue;
+                }
+                Err(e) => format!("parse error: {}", e),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                format!("{} not found", filename.display())
+            }
+            Err(e) => return Err(format!("{}: {}", filename.display(), e).into()),
+        };
+
+        match format {
+            Format::Text => table.row([day.to_string(), "-".into(), format!("skipped, {}", skip)]),
+            Format::Json => println!("{}", Json::object().with("day", day).with("skipped", skip)),
+        }
+    }
+
+    if format == Format::Text {
+        print!("{}", table);
+    }
+
+    Ok(())
+}
+
+/// Runs the registered solutions (or only the given day) and compares their answers with
+/// the known-correct ones, printing a report. Fails if any answer doesn't match.
+pub fn verify(
+    answers: &Answers,
+    only_day: Option<u32>,
+    example: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut table = Table::new(["Day", "Part", "Status", "Answer", "Expected"]);
+    let mut failures = 0;
+
+    for &(day, solution) in SOLUTIONS {
+        if only_day.is_some_and(|d| d != day) {
+            continue;
+        }
+
+        let filename = input_path(day, example);
+
+        let input = match fs::read_to_string(&filename) {
+            Ok(input) => solution.parse_any(&input[..]).map_err(|e| e.with_day(day)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                table.row([day.to_string(), "-".into(), "missing".into(), format!("{} not found", filename.display())]);
+                continue;
+            }
+            Err(e) => return Err(format!("{}: {}", filename.display(), e).into()),
+        };
+
+        for part in Part::ALL {
+            let expected = answers.get(day, part);
+
+            let answer = match &input {
+                Ok(input) => solution.solve_any(&**input, part),
+                Err(e) => Err(format!("parse error: {}", e).into()),
+            };
+
+            let (status, answer) = match (answer, expected) {
+                (Ok(answer), Some(expected)) if answer.value == expected => ("pass", answer.value),
+                (Ok(answer), None) => ("missing", answer.value),
+                (Ok(answer), Some(_)) => ("FAIL", answer.value),
+                (Err(e), Some(_)) => ("FAIL", format!("error: {}", e)),
+                (Err(e), None) => ("missing", format!("error: {}", e)),
+            };
+
+            if status == "FAIL" {
+                failures += 1;
+            }
+
+            table.row([
+                day.to_string(),
+                part.to_string(),
+                status.to_owned(),
+                answer,
+                expected.unwrap_or("-").to_owned(),
+            ]);
+        }
+    }
+
+    print!("{}", table);
+
+    if failures > 0 {
+        return Err(format!("{} answer(s) did not match", failures).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Source of a new day module, with `DayN` standing for the name of the solution type
+const TEMPLATE: &str = include_str!("day_template.rs");
+
+/// Creates `src/day_N.rs` under the given project root from the day template.
+/// The build script picks the new module up and registers its solution automatically, along with
+/// example tests, which fail until `inputs/dayN-example.txt` and the expected answers in
+/// `inputs/examples.toml` are added.
+///
+/// Fails if the module already exists.
+pub fn new_day(root: &Path, day: u32) -> Result<PathBuf, Box<dyn Error>> {
+    let src = root.join("src");
+    if !src.is_dir() {
+        return Err(format!("{} is not a directory, run from the project root", src.display()).into());
+    }
+
+    let path = src.join(format!("day_{}.rs", day));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()).into());
+    }
+
+    fs::write(&path, TEMPLATE.replace("DayN", &format!("Day{}", day)))?;
+
+    Ok(path)
+}
@@ -0,0 +1,283 @@
+use std::{any::Any, collections::BTreeMap, error::Error, fmt, str::FromStr};
+
+use crate::parse::ParseError;
+
+/// Selects which part of a day's puzzle to solve
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub const fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part `{}`, expected 1 or 2", s)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// The answer to one part of a puzzle, optionally accompanied by named intermediate values
+/// which led to it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub value: String,
+    pub extra: Vec<(&'static str, String)>,
+}
+
+impl Answer {
+    pub fn new(value: impl fmt::Display) -> Self {
+        Self {
+            value: value.to_string(),
+            extra: vec![],
+        }
+    }
+
+    /// Attaches a named intermediate value
+    pub fn with(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.extra.push((name, value.to_string()));
+        self
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+macro_rules! impl_answer_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Self::new(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from!(u32, u64, usize, i64, String);
+
+/// Named settings of a solution, given on the command line as `key=value`
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Params(BTreeMap<String, String>);
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    /// Parses the value of a setting, if it's given
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.0
+            .get(key)
+            .map(|value| value.parse().map_err(|e| format!("invalid value of `{}`: {}", key, e).into()))
+            .transpose()
+    }
+
+    /// Parses the value of a setting as a list of values separated by `sep`, if it's given
+    pub fn get_list<T>(&self, key: &str, sep: char) -> Result<Option<Vec<T>>, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.0
+            .get(key)
+            .map(|value| {
+                value
+                    .split(sep)
+                    .map(|item| item.trim().parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| format!("invalid value of `{}`: {}", key, e).into())
+            })
+            .transpose()
+    }
+
+    /// Parses the value of a setting, falling back to `default` if it's not given
+    pub fn get_or<T>(&self, key: &str, default: T) -> Result<T, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Fails if any setting other than the given ones is present
+    pub fn expect(&self, keys: &[&str]) -> Result<(), Box<dyn Error>> {
+        match self.0.keys().find(|key| !keys.contains(&&key[..])) {
+            Some(key) if keys.is_empty() => Err(format!("unexpected setting `{}`", key).into()),
+            Some(key) => Err(format!("unexpected setting `{}`, expected one of: {}", key, keys.join(", ")).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a single `key=value` setting
+impl FromStr for Params {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid setting `{}`, expected `key=value`", s))?;
+
+        let mut params = Self::new();
+        params.set(key.trim(), value.trim());
+        Ok(params)
+    }
+}
+
+impl Extend<(String, String)> for Params {
+    fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for Params {
+    type Item = (String, String);
+    type IntoIter = std::collections::btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// A solution to a single day's puzzle. The input is parsed once and can then be passed
+/// to either part.
+pub trait Solution {
+    type Input;
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part_one(input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>>;
+
+    fn part_two(input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>>;
+
+    /// Names of the settings the solution accepts, used by [`Self::configure`] and [`Self::report`]
+    const PARAMS: &'static [&'static str] = &[];
+
+    /// Applies settings, all of them among [`Self::PARAMS`], to the parsed input before solving,
+    /// e.g. the number of days to simulate
+    fn configure(_input: &mut Self::Input, _params: &Params) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Names of the additional reports on the input the solution can produce, e.g. `svg`
+    const REPORTS: &'static [&'static str] = &[];
+
+    /// Produces the report with the given name, one of [`Self::REPORTS`], with optional settings
+    fn report(_input: &Self::Input, name: &str, _params: &Params) -> Result<String, Box<dyn Error>> {
+        Err(format!("no report named `{}`", name).into())
+    }
+}
+
+/// Object-safe counterpart of [`Solution`] with the input and answer types erased,
+/// so that solutions for different days can be stored together in a registry.
+pub trait DynSolution {
+    /// Parses the input into an opaque value which can only be passed back to [`Self::solve_any`]
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+
+    /// Solves the given part for input previously returned by [`Self::parse_any`].
+    ///
+    /// # Panics
+    /// Panics if the input was parsed by a different solution.
+    fn solve_any(&self, input: &dyn Any, part: Part) -> Result<Answer, Box<dyn Error>>;
+
+    /// Names of the settings the solution accepts
+    fn params(&self) -> &'static [&'static str];
+
+    /// Applies settings to input previously returned by [`Self::parse_any`], failing if any of
+    /// them isn't accepted by the solution.
+    ///
+    /// # Panics
+    /// Panics if the input was parsed by a different solution.
+    fn configure_any(&self, input: &mut dyn Any, params: &Params) -> Result<(), Box<dyn Error>>;
+
+    /// Names of the reports the solution can produce
+    fn reports(&self) -> &'static [&'static str];
+
+    /// Produces a report on input previously returned by [`Self::parse_any`].
+    ///
+    /// # Panics
+    /// Panics if the input was parsed by a different solution.
+    fn report_any(&self, input: &dyn Any, name: &str, params: &Params) -> Result<String, Box<dyn Error>>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn solve_any(&self, input: &dyn Any, part: Part) -> Result<Answer, Box<dyn Error>> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input parsed by a different solution");
+
+        Ok(match part {
+            Part::One => S::part_one(input)?.into(),
+            Part::Two => S::part_two(input)?.into(),
+        })
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        S::PARAMS
+    }
+
+    fn configure_any(&self, input: &mut dyn Any, params: &Params) -> Result<(), Box<dyn Error>> {
+        let input = input
+            .downcast_mut::<S::Input>()
+            .expect("input parsed by a different solution");
+
+        params.expect(S::PARAMS)?;
+        S::configure(input, params)
+    }
+
+    fn reports(&self) -> &'static [&'static str] {
+        S::REPORTS
+    }
+
+    fn report_any(&self, input: &dyn Any, name: &str, params: &Params) -> Result<String, Box<dyn Error>> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input parsed by a different solution");
+
+        S::report(input, name, params)
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt::{self, Write};
+
+/// A plain text table with columns aligned to their widest cell
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I, S>(header: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        Self {
+            header: header.into_iter().map(|s| s.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    /// Appends a row. Missing trailing cells are left empty.
+    pub fn row<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.rows.push(row.into_iter().map(|s| s.to_string()).collect());
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<_> = self.header.iter().map(|h| h.chars().count()).collect();
+
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        widths
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[String]| {
+            for (i, &width) in widths.iter().enumerate() {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+
+                if i + 1 < widths.len() {
+                    write!(f, "{:<width$}  ", cell, width = width)?;
+                } else {
+                    f.write_str(cell)?;
+                }
+            }
+            f.write_char('\n')
+        };
+
+        write_row(f, &self.header)?;
+
+        let rule_width = widths.iter().sum::<usize>() + 2 * widths.len().saturating_sub(1);
+        writeln!(f, "{}", "-".repeat(rule_width))?;
+
+        for row in &self.rows {
+            write_row(f, row)?;
+        }
+
+        Ok(())
+    }
+}
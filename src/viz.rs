@@ -0,0 +1,296 @@
+//! Frame-by-frame visualisation of solutions.
+//!
+//! Solutions emit frames with [`emit`], which only builds them while they're being recorded
+//! with [`record`], so emitting costs next to nothing in normal runs. Recorded frames can be
+//! played back in the terminal with [`play`].
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt::{self, Write as _},
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::grid::Grid;
+
+thread_local! {
+    static FRAMES: RefCell<Option<Vec<Frame>>> = const { RefCell::new(None) };
+}
+
+/// Contents of a single frame
+#[derive(Clone, Debug)]
+pub enum Content {
+    /// Preformatted text, possibly with ANSI colours
+    Text(String),
+    /// A grid of values, e.g. heights or counts
+    Grid(Grid<u32>),
+}
+
+/// A single step of a visualisation
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub caption: String,
+    pub content: Content,
+    /// Positions of highlighted grid cells
+    pub highlights: HashSet<(usize, usize)>,
+    /// Value of grid cells drawn as empty, e.g. zero counts
+    pub blank: Option<u32>,
+}
+
+impl Frame {
+    pub fn text(caption: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(caption, Content::Text(text.into()))
+    }
+
+    pub fn grid(caption: impl Into<String>, grid: Grid<u32>) -> Self {
+        Self::new(caption, Content::Grid(grid))
+    }
+
+    fn new(caption: impl Into<String>, content: Content) -> Self {
+        Self {
+            caption: caption.into(),
+            content,
+            highlights: HashSet::new(),
+            blank: None,
+        }
+    }
+
+    /// Highlights the grid cells at the given positions
+    pub fn with_highlights(mut self, cells: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        self.highlights.extend(cells);
+        self
+    }
+
+    /// Draws grid cells with the given value as empty
+    pub fn with_blank(mut self, value: u32) -> Self {
+        self.blank = Some(value);
+        self
+    }
+}
+
+/// Renders the frame for the terminal, with highlights in bold yellow
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}\n", self.caption)?;
+
+        let grid = match &self.content {
+            Content::Text(text) => return f.write_str(text),
+            Content::Grid(grid) => grid,
+        };
+
+        let width = grid
+            .iter()
+            .filter(|&&v| Some(v) != self.blank)
+            .map(|v| v.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        for row in 0..grid.height() {
+            let mut line = String::new();
+
+            for col in 0..grid.width() {
+                if col > 0 && width > 1 {
+                    line.push(' ');
+                }
+
+                let value = grid[(col, row)];
+                let cell = match Some(value) == self.blank {
+                    true => format!("{:>width$}", '.', width = width),
+                    false => format!("{:>width$}", value, width = width),
+                };
+
+                if self.highlights.contains(&(col, row)) {
+                    write!(line, "\x1b[1;93m{}\x1b[0m", cell)?;
+                } else {
+                    line.push_str(&cell);
+                }
+            }
+
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if frames are being recorded on this thread
+pub fn enabled() -> bool {
+    FRAMES.with(|frames| frames.borrow().is_some())
+}
+
+/// Records the frame built by `frame`, if frames are being recorded on this thread
+pub fn emit<F>(frame: F)
+where
+    F: FnOnce() -> Frame,
+{
+    FRAMES.with(|frames| {
+        if let Some(frames) = frames.borrow_mut().as_mut() {
+            frames.push(frame());
+        }
+    });
+}
+
+/// Runs `f`, recording the frames it emits
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<Frame>) {
+    let outer = FRAMES.with(|frames| frames.replace(Some(vec![])));
+    let result = f();
+    let recorded = FRAMES.with(|frames| frames.replace(outer)).unwrap_or_default();
+
+    (result, recorded)
+}
+
+/// Settings of the playback of frames
+#[derive(Clone, Copy, Debug)]
+pub struct Playback {
+    pub fps: f64,
+    /// Start paused, waiting for steps
+    pub paused: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            fps: 4.0,
+            paused: false,
+        }
+    }
+}
+
+/// Terminal switched to unbuffered, silent input for reading keys, until dropped
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    /// Switches the terminal attached to the standard input, if any, using `stty`
+    fn enable() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "0"])?;
+
+        Some(Self { saved: saved.trim().to_owned() })
+    }
+
+    /// Returns the next pressed key, if any, without waiting
+    fn key(&self) -> Option<u8> {
+        let mut key = [0];
+        match io::stdin().read(&mut key) {
+            Ok(1) => Some(key[0]),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+const CONTROLS: &str = "[space] pause  [n] next  [b] back  [+/-] speed  [q] quit";
+
+/// Plays the frames back in the terminal. If the standard input is a terminal, playback can
+/// be paused, stepped through, sped up or slowed down, and quit with the keys listed below it.
+pub fn play(frames: &[Frame], playback: Playback) -> io::Result<()> {
+    if frames.is_empty() {
+        return Ok(());
+    }
+
+    let raw = RawMode::enable();
+    let mut stdout = io::stdout().lock();
+
+    let mut fps = playback.fps.max(0.1);
+    let mut paused = playback.paused && raw.is_some();
+    let mut index = 0;
+
+    loop {
+        write!(stdout, "\x1b[2J\x1b[H{}", frames[index])?;
+        write!(stdout, "\nframe {}/{}  {:.1} fps{}", index + 1, frames.len(), fps, if paused { "  paused" } else { "" })?;
+        if raw.is_some() {
+            write!(stdout, "  {}", CONTROLS)?;
+        }
+        writeln!(stdout)?;
+        stdout.flush()?;
+
+        let deadline = Instant::now() + Duration::from_secs_f64(1.0 / fps);
+        let mut redraw = false;
+
+        while !redraw && (paused || Instant::now() < deadline) {
+            match raw.as_ref().and_then(RawMode::key) {
+                Some(b' ') => {
+                    paused = !paused;
+                    redraw = true;
+                }
+                Some(b'n') if index + 1 < frames.len() => {
+                    index += 1;
+                    redraw = true;
+                }
+                Some(b'b') if index > 0 => {
+                    index -= 1;
+                    redraw = true;
+                }
+                Some(b'+') => {
+                    fps = (fps * 2.0).min(1000.0);
+                    redraw = true;
+                }
+                Some(b'-') => {
+                    fps = (fps / 2.0).max(0.1);
+                    redraw = true;
+                }
+                Some(b'q') => return Ok(()),
+                _ => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+
+        if !redraw {
+            if index + 1 == frames.len() {
+                return Ok(());
+            }
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_frames() {
+        emit(|| unreachable!("frames are only built while recording"));
+
+        let (answer, frames) = record(|| {
+            assert!(enabled());
+            emit(|| Frame::text("hello", "world"));
+            emit(|| {
+                Frame::grid("grid", Grid::from_rows([[0, 12], [3, 0]]))
+                    .with_blank(0)
+                    .with_highlights([(0, 1)])
+            });
+            42
+        });
+
+        assert_eq!(answer, 42);
+        assert!(!enabled());
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].to_string(), "hello\n\nworld");
+        assert_eq!(frames[1].to_string(), "grid\n\n . 12\n\x1b[1;93m 3\x1b[0m  .\n");
+    }
+}
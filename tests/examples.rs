@@ -0,0 +1,47 @@
+//! Regression tests running every day's solution on its example input from
+//! `inputs/dayN-example.txt` and checking the answers stored in `inputs/examples.toml`.
+//! A day without an example input or expected answers fails its tests.
+
+use std::{fs, path::Path};
+
+use aoc_2021::{answers::Answers, solution::Part};
+
+fn check(day: u32, part: Part) {
+    let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+
+    let answers = Answers::load(&inputs.join("examples.toml")).unwrap();
+    let expected = answers.get(day, part).unwrap_or_else(|| {
+        panic!("no expected answer for day {} part {}, add `part_{}` under `[day_{}]` to inputs/examples.toml", day, part, part, day)
+    });
+
+    let path = inputs.join(format!("day{}-example.txt", day));
+    let input = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+
+    let solution = aoc_2021::solution(day).unwrap();
+    let input = solution.parse_any(&input[..]).unwrap();
+
+    assert_eq!(solution.solve_any(&*input, part).unwrap().value, expected);
+}
+
+macro_rules! example_tests {
+    ($($name:ident: $day:literal,)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn part_1() {
+                    check($day, Part::One);
+                }
+
+                #[test]
+                fn part_2() {
+                    check($day, Part::Two);
+                }
+            }
+        )*
+    };
+}
+
+// One test module per registered day, generated by the build script
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));